@@ -0,0 +1,327 @@
+// Generates the gnark fixtures of the verifier crate: a PLONK proof with a BSB22 commitment and a
+// Groth16 proof with two Pedersen commitments.
+//
+//	go mod tidy && go run . -out ..
+//
+// The PLONK setup uses an unsafe KZG SRS and the Groth16 setup keeps its toxic waste, which are
+// only fit for test vectors.
+package main
+
+import (
+	"bytes"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"flag"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/consensys/gnark-crypto/ecc/bn254"
+	"github.com/consensys/gnark-crypto/ecc/bn254/fr"
+	"github.com/consensys/gnark/backend/groth16"
+	groth16_bn254 "github.com/consensys/gnark/backend/groth16/bn254"
+	"github.com/consensys/gnark/backend/plonk"
+	plonk_bn254 "github.com/consensys/gnark/backend/plonk/bn254"
+	"github.com/consensys/gnark/frontend"
+	"github.com/consensys/gnark/frontend/cs/r1cs"
+	"github.com/consensys/gnark/frontend/cs/scs"
+	"github.com/consensys/gnark/test/unsafekzg"
+)
+
+// Proves knowledge of a and b with a * b = X and a + b = Y, committing to both.
+type circuit struct {
+	X frontend.Variable `gnark:",public"`
+	Y frontend.Variable `gnark:",public"`
+	A frontend.Variable
+	B frontend.Variable
+}
+
+func (c *circuit) Define(api frontend.API) error {
+	committer, ok := api.(frontend.Committer)
+	if !ok {
+		return errors.New("the builder does not support commitments")
+	}
+	commitment, err := committer.Commit(c.A, c.B)
+	if err != nil {
+		return err
+	}
+	api.AssertIsEqual(api.Mul(c.A, c.B), c.X)
+	api.AssertIsEqual(api.Add(c.A, c.B), c.Y)
+	api.AssertIsDifferent(commitment, 0)
+	return nil
+}
+
+// The circuit of `circuit` with two commitments, the first to X and a, the second to Y and b, so
+// both commit to a public input.
+type twoCommitmentsCircuit circuit
+
+func (c *twoCommitmentsCircuit) Define(api frontend.API) error {
+	committer, ok := api.(frontend.Committer)
+	if !ok {
+		return errors.New("the builder does not support commitments")
+	}
+	first, err := committer.Commit(c.X, c.A)
+	if err != nil {
+		return err
+	}
+	second, err := committer.Commit(c.Y, c.B)
+	if err != nil {
+		return err
+	}
+	api.AssertIsEqual(api.Mul(c.A, c.B), c.X)
+	api.AssertIsEqual(api.Add(c.A, c.B), c.Y)
+	api.AssertIsDifferent(first, 0)
+	api.AssertIsDifferent(second, 0)
+	return nil
+}
+
+// The JSON read by `execute-plonk` and the crate's tests.
+type plonkFixture struct {
+	VerifyingKey string   `json:"verifying_key"`
+	Proof        string   `json:"proof"`
+	PublicInputs []string `json:"public_inputs"`
+}
+
+func main() {
+	out := flag.String("out", "..", "the fixture directory")
+	flag.Parse()
+
+	fixture, err := provePlonk()
+	if err != nil {
+		log.Fatal(err)
+	}
+	if err := writeJSON(filepath.Join(*out, "plonk", "commitment.json"), fixture); err != nil {
+		log.Fatal(err)
+	}
+
+	groth16Fixture, err := proveGroth16()
+	if err != nil {
+		log.Fatal(err)
+	}
+	if err := writeJSON(filepath.Join(*out, "groth16", "commitments.json"), groth16Fixture); err != nil {
+		log.Fatal(err)
+	}
+}
+
+func provePlonk() (*plonkFixture, error) {
+	ccs, err := frontend.Compile(ecc.BN254.ScalarField(), scs.NewBuilder, &circuit{})
+	if err != nil {
+		return nil, err
+	}
+	srs, srsLagrange, err := unsafekzg.NewSRS(ccs)
+	if err != nil {
+		return nil, err
+	}
+	pk, vk, err := plonk.Setup(ccs, srs, srsLagrange)
+	if err != nil {
+		return nil, err
+	}
+
+	assignment := circuit{X: 35, Y: 12, A: 5, B: 7}
+	witness, err := frontend.NewWitness(&assignment, ecc.BN254.ScalarField())
+	if err != nil {
+		return nil, err
+	}
+	public, err := witness.Public()
+	if err != nil {
+		return nil, err
+	}
+	proof, err := plonk.Prove(ccs, pk, witness)
+	if err != nil {
+		return nil, err
+	}
+	if err := plonk.Verify(proof, vk, public); err != nil {
+		return nil, err
+	}
+
+	var proofBytes bytes.Buffer
+	if _, err := proof.(*plonk_bn254.Proof).WriteRawTo(&proofBytes); err != nil {
+		return nil, err
+	}
+	vkBytes, err := encodePlonkVerifyingKey(vk.(*plonk_bn254.VerifyingKey))
+	if err != nil {
+		return nil, err
+	}
+	return &plonkFixture{
+		VerifyingKey: hex.EncodeToString(vkBytes),
+		Proof:        hex.EncodeToString(proofBytes.Bytes()),
+		PublicInputs: encodeScalars(public.Vector().(fr.Vector)),
+	}, nil
+}
+
+// The fields `PlonkVerifyingKey` decodes, each in gnark's raw encoding. The key's own
+// `WriteRawTo` also writes the precomputed pairing lines of the KZG key, which the crate does
+// not use.
+func encodePlonkVerifyingKey(vk *plonk_bn254.VerifyingKey) ([]byte, error) {
+	var buf bytes.Buffer
+	enc := bn254.NewEncoder(&buf, bn254.RawEncoding())
+	for _, v := range []interface{}{
+		vk.Size,
+		&vk.SizeInv,
+		&vk.Generator,
+		vk.NbPublicVariables,
+		&vk.CosetShift,
+		&vk.S[0],
+		&vk.S[1],
+		&vk.S[2],
+		&vk.Ql,
+		&vk.Qr,
+		&vk.Qm,
+		&vk.Qo,
+		&vk.Qk,
+		vk.Qcp,
+		&vk.Kzg.G1,
+		&vk.Kzg.G2[0],
+		&vk.Kzg.G2[1],
+		vk.CommitmentConstraintIndexes,
+	} {
+		if err := enc.Encode(v); err != nil {
+			return nil, err
+		}
+	}
+	return buf.Bytes(), nil
+}
+
+// The JSON encodings of the crate's `VerifyingKey` and `ZKP`: hex encoded uncompressed points.
+type groth16Fixture struct {
+	VerifyingKey groth16VerifyingKey `json:"verifying_key"`
+	Proof        groth16Proof        `json:"proof"`
+	PublicInputs []string            `json:"public_inputs"`
+}
+
+type groth16VerifyingKey struct {
+	AlphaG1                      string         `json:"alpha_g1"`
+	BetaG2                       string         `json:"beta_g2"`
+	GammaG2                      string         `json:"gamma_g2"`
+	DeltaG2                      string         `json:"delta_g2"`
+	GammaAbcG1                   []string       `json:"gamma_abc_g1"`
+	CommitmentKey                *commitmentKey `json:"commitment_key"`
+	PublicAndCommitmentCommitted [][]int        `json:"public_and_commitment_committed"`
+	CommitmentHash               string         `json:"commitment_hash"`
+}
+
+type commitmentKey struct {
+	G             string `json:"g"`
+	GRootSigmaNeg string `json:"g_root_sigma_neg"`
+}
+
+type groth16Proof struct {
+	Proof struct {
+		A string `json:"a"`
+		B string `json:"b"`
+		C string `json:"c"`
+	} `json:"proof"`
+	ProofCommitments   []string `json:"proof_commitments"`
+	ProofCommitmentPok string   `json:"proof_commitment_pok"`
+}
+
+func proveGroth16() (*groth16Fixture, error) {
+	ccs, err := frontend.Compile(ecc.BN254.ScalarField(), r1cs.NewBuilder, &twoCommitmentsCircuit{})
+	if err != nil {
+		return nil, err
+	}
+	pk, vk, err := groth16.Setup(ccs)
+	if err != nil {
+		return nil, err
+	}
+
+	assignment := twoCommitmentsCircuit{X: 35, Y: 12, A: 5, B: 7}
+	witness, err := frontend.NewWitness(&assignment, ecc.BN254.ScalarField())
+	if err != nil {
+		return nil, err
+	}
+	public, err := witness.Public()
+	if err != nil {
+		return nil, err
+	}
+	proof, err := groth16.Prove(ccs, pk, witness)
+	if err != nil {
+		return nil, err
+	}
+	if err := groth16.Verify(proof, vk, public); err != nil {
+		return nil, err
+	}
+
+	key := vk.(*groth16_bn254.VerifyingKey)
+	p := proof.(*groth16_bn254.Proof)
+	if len(p.Commitments) != 2 {
+		return nil, fmt.Errorf("expected two commitments, got %d", len(p.Commitments))
+	}
+	// The crate verifies every commitment with the same key.
+	for _, other := range key.CommitmentKeys[1:] {
+		if !other.G.Equal(&key.CommitmentKeys[0].G) ||
+			!other.GRootSigmaNeg.Equal(&key.CommitmentKeys[0].GRootSigmaNeg) {
+			return nil, errors.New("the commitments have different keys, which the crate does not support")
+		}
+	}
+
+	fixture := groth16Fixture{
+		VerifyingKey: groth16VerifyingKey{
+			AlphaG1:    encodeG1(&key.G1.Alpha),
+			BetaG2:     encodeG2(&key.G2.Beta),
+			GammaG2:    encodeG2(&key.G2.Gamma),
+			DeltaG2:    encodeG2(&key.G2.Delta),
+			GammaAbcG1: make([]string, len(key.G1.K)),
+			CommitmentKey: &commitmentKey{
+				G:             encodeG2(&key.CommitmentKeys[0].G),
+				GRootSigmaNeg: encodeG2(&key.CommitmentKeys[0].GRootSigmaNeg),
+			},
+			PublicAndCommitmentCommitted: make([][]int, len(key.PublicAndCommitmentCommitted)),
+			CommitmentHash:               "Gnark",
+		},
+		PublicInputs:     encodeScalars(public.Vector().(fr.Vector)),
+	}
+	for i := range key.G1.K {
+		fixture.VerifyingKey.GammaAbcG1[i] = encodeG1(&key.G1.K[i])
+	}
+	// gnark counts the constant one, the crate does not.
+	for i, committed := range key.PublicAndCommitmentCommitted {
+		fixture.VerifyingKey.PublicAndCommitmentCommitted[i] = make([]int, len(committed))
+		for j, index := range committed {
+			fixture.VerifyingKey.PublicAndCommitmentCommitted[i][j] = index - 1
+		}
+	}
+	fixture.Proof.Proof.A = encodeG1(&p.Ar)
+	fixture.Proof.Proof.B = encodeG2(&p.Bs)
+	fixture.Proof.Proof.C = encodeG1(&p.Krs)
+	fixture.Proof.ProofCommitments = make([]string, len(p.Commitments))
+	for i := range p.Commitments {
+		fixture.Proof.ProofCommitments[i] = encodeG1(&p.Commitments[i])
+	}
+	fixture.Proof.ProofCommitmentPok = encodeG1(&p.CommitmentPok)
+	return &fixture, nil
+}
+
+func encodeG1(point *bn254.G1Affine) string {
+	b := point.RawBytes()
+	return hex.EncodeToString(b[:])
+}
+
+// `x.A1 ‖ x.A0 ‖ y.A1 ‖ y.A0`, the imaginary parts first like the crate's `G2AffineBE`.
+func encodeG2(point *bn254.G2Affine) string {
+	b := point.RawBytes()
+	return hex.EncodeToString(b[:])
+}
+
+func encodeScalars(scalars []fr.Element) []string {
+	encoded := make([]string, len(scalars))
+	for i := range scalars {
+		b := scalars[i].Bytes()
+		encoded[i] = hex.EncodeToString(b[:])
+	}
+	return encoded
+}
+
+func writeJSON(path string, value interface{}) error {
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	data, err := json.MarshalIndent(value, "", "  ")
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(path, append(data, '\n'), 0o644)
+}
@@ -0,0 +1,250 @@
+//! Proves several headers either separately and in parallel, composed by the aggregator guest
+//! which verifies their receipts as assumptions, or all at once in a single execution of the
+//! batch guest, and compares the end-to-end latency of both.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{ensure, Context, Result};
+use cometbls_groth16_verifier::VerifyZkpRequest;
+use methods::{
+    GUEST_CODE_FOR_ZK_PROOF_AGGREGATE_ELF, GUEST_CODE_FOR_ZK_PROOF_BATCH_ELF,
+    GUEST_CODE_FOR_ZK_PROOF_ID,
+};
+use risc0_zkvm::{default_prover, ProveInfo, ProverOpts, Receipt};
+use serde::Serialize;
+
+use crate::prove::{env_builder, prove_request, ProverMode};
+
+/// The result of [`prove_aggregated`].
+#[derive(Debug)]
+pub struct AggregateProveInfo {
+    /// The receipt of the aggregator guest, its journal is `(image_id, results)`, where
+    /// `results` is the digest of every request and whether it is valid.
+    pub receipt: Receipt,
+    /// The succinct receipts of the headers, in request order.
+    pub header_receipts: Vec<Receipt>,
+    /// Whether each header is valid, in request order.
+    pub results: Vec<bool>,
+    pub headers_duration: Duration,
+    pub aggregate_duration: Duration,
+    /// The total cycles of the headers and the aggregator guest.
+    pub total_cycles: u64,
+}
+
+/// Runs `f` on every item on at most `workers` threads, returning the results in the order of
+/// `items`, or the first error.
+pub fn map_bounded<T, R, F>(items: &[T], workers: usize, f: F) -> Result<Vec<R>>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> Result<R> + Sync,
+{
+    ensure!(workers > 0, "at least one worker is required");
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<R>>>> = Mutex::new(items.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..workers.min(items.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                let result = f(item);
+                let failed = result.is_err();
+                results.lock().unwrap()[i] = Some(result);
+                if failed {
+                    // Let the other workers stop after their current item.
+                    next.store(items.len(), Ordering::Relaxed);
+                    break;
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .map(|(i, result)| result.with_context(|| format!("item {i} was not processed"))?)
+        .collect()
+}
+
+/// Proves every request into a succinct receipt on at most `workers` threads, which can be used
+/// as an assumption.
+pub fn prove_headers(requests: &[VerifyZkpRequest], workers: usize) -> Result<Vec<ProveInfo>> {
+    map_bounded(requests, workers, |request| {
+        prove_request(request, &ProverOpts::succinct())
+    })
+}
+
+/// Proves the aggregator guest over `header_receipts`, which must be receipts of the verifier
+/// guest. Their journals, the digest of the request and its result, are what the aggregator
+/// verifies and commits.
+pub fn aggregate(header_receipts: &[Receipt], opts: &ProverOpts) -> Result<ProveInfo> {
+    let results = header_receipts
+        .iter()
+        .map(|receipt| receipt.journal.decode())
+        .collect::<Result<Vec<([u8; 32], bool)>, _>>()?;
+
+    let mut env = env_builder()?;
+    env.write(&(GUEST_CODE_FOR_ZK_PROOF_ID, results))?;
+    for receipt in header_receipts {
+        env.add_assumption(receipt.clone());
+    }
+
+    default_prover().prove_with_opts(env.build()?, GUEST_CODE_FOR_ZK_PROOF_AGGREGATE_ELF, opts)
+}
+
+/// Proves every request separately on at most `workers` threads, then aggregates the receipts.
+pub fn prove_aggregated(
+    requests: &[VerifyZkpRequest],
+    workers: usize,
+    opts: &ProverOpts,
+) -> Result<AggregateProveInfo> {
+    let start = Instant::now();
+    let headers = prove_headers(requests, workers)?;
+    let headers_duration = start.elapsed();
+    let mut total_cycles: u64 = headers.iter().map(|header| header.stats.total_cycles).sum();
+    let header_receipts: Vec<Receipt> = headers.into_iter().map(|header| header.receipt).collect();
+
+    let start = Instant::now();
+    let ProveInfo { receipt, stats } = aggregate(&header_receipts, opts)?;
+    let aggregate_duration = start.elapsed();
+    total_cycles += stats.total_cycles;
+
+    let (image_id, committed): ([u32; 8], Vec<([u8; 32], bool)>) = receipt.journal.decode()?;
+    ensure!(
+        image_id == GUEST_CODE_FOR_ZK_PROOF_ID,
+        "the aggregator verified receipts of another guest"
+    );
+    ensure!(
+        committed.len() == requests.len(),
+        "the aggregator committed {} results for {} requests",
+        committed.len(),
+        requests.len()
+    );
+    let results = committed
+        .iter()
+        .zip(requests)
+        .map(|(&(request_digest, valid), request)| {
+            ensure!(
+                request_digest == request.digest(),
+                "the aggregator committed the result of another request"
+            );
+            Ok(valid)
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(AggregateProveInfo {
+        receipt,
+        header_receipts,
+        results,
+        headers_duration,
+        aggregate_duration,
+        total_cycles,
+    })
+}
+
+/// Proves every request in a single execution of the batch guest, its journal is the result of
+/// every request.
+pub fn prove_batch(requests: &[VerifyZkpRequest], opts: &ProverOpts) -> Result<ProveInfo> {
+    let env = env_builder()?.write(&requests)?.build()?;
+
+    default_prover().prove_with_opts(env, GUEST_CODE_FOR_ZK_PROOF_BATCH_ELF, opts)
+}
+
+/// The end-to-end latency of both approaches for the same requests, written as JSON.
+#[derive(Debug, Serialize)]
+pub struct LatencyComparison {
+    pub prover: ProverMode,
+    pub headers: usize,
+    pub workers: usize,
+    /// Proving the headers in parallel.
+    pub headers_millis: u64,
+    /// Proving the aggregator guest.
+    pub aggregate_millis: u64,
+    /// `headers_millis + aggregate_millis`.
+    pub aggregated_millis: u64,
+    /// Proving the batch guest.
+    pub batch_millis: u64,
+    /// The total cycles of the headers and the aggregator guest.
+    pub aggregated_total_cycles: u64,
+    pub batch_total_cycles: u64,
+    pub results: Vec<bool>,
+}
+
+/// Proves `requests` with both approaches, checking they agree.
+pub fn compare_latency(
+    requests: &[VerifyZkpRequest],
+    workers: usize,
+    opts: &ProverOpts,
+) -> Result<LatencyComparison> {
+    let aggregated = prove_aggregated(requests, workers, opts)?;
+
+    let start = Instant::now();
+    let batch = prove_batch(requests, opts)?;
+    let batch_duration = start.elapsed();
+
+    let batch_results: Vec<bool> = batch.receipt.journal.decode()?;
+    ensure!(
+        batch_results == aggregated.results,
+        "the batch guest committed {batch_results:?}, the aggregated proofs {:?}",
+        aggregated.results
+    );
+
+    let headers_millis = millis(aggregated.headers_duration);
+    let aggregate_millis = millis(aggregated.aggregate_duration);
+    Ok(LatencyComparison {
+        prover: ProverMode::current()?,
+        headers: requests.len(),
+        workers,
+        headers_millis,
+        aggregate_millis,
+        aggregated_millis: headers_millis + aggregate_millis,
+        batch_millis: millis(batch_duration),
+        aggregated_total_cycles: aggregated.total_cycles,
+        batch_total_cycles: batch.stats.total_cycles,
+        results: aggregated.results,
+    })
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+
+    #[test]
+    fn test_map_bounded() {
+        let items: Vec<u64> = (0..20).collect();
+        for workers in [1, 3, 50] {
+            assert_eq!(
+                map_bounded(&items, workers, |item| Ok(item * 2)).unwrap(),
+                items.iter().map(|item| item * 2).collect::<Vec<_>>()
+            );
+        }
+
+        let err = map_bounded(&items, 3, |&item| {
+            if item == 5 {
+                bail!("failed on {item}");
+            }
+            Ok(item)
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "failed on 5");
+        assert!(map_bounded(&items, 0, |&item| Ok(item)).is_err());
+    }
+}
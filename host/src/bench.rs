@@ -0,0 +1,311 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{ensure, Result};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{
+    fixtures::Fixture,
+    memory::{read_kb_field, track_peak_rss},
+    prove::{
+        prove_request, prove_request_staged, prover_opts, Guest, ProverBackend, ProverMode, Stages,
+    },
+    results::{log_results, Stats},
+};
+
+/// A complete benchmark run, written as JSON.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub started_at: String,
+    pub machine: MachineInfo,
+    pub prover: ProverMode,
+    pub warmup: usize,
+    pub iterations: usize,
+    pub scenarios: Vec<ScenarioResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScenarioResult {
+    pub name: String,
+    pub summary: Summary,
+    pub segments: usize,
+    pub total_cycles: u64,
+    pub user_cycles: u64,
+    /// The duration of every measured iteration, in order.
+    pub durations_millis: Vec<u64>,
+    /// The highest peak resident memory of the measured iterations, not measured on Bonsai.
+    pub peak_rss_bytes: Option<u64>,
+    /// The median of every stage, not measured on Bonsai.
+    pub stages: Option<Stages>,
+    /// The stages of every measured iteration, in order.
+    pub iteration_stages: Vec<Stages>,
+}
+
+/// Duration statistics over the measured iterations of a scenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub min_millis: u64,
+    pub median_millis: u64,
+    pub p95_millis: u64,
+}
+
+impl Summary {
+    /// Uses the nearest-rank method for the percentiles, `durations` must not be empty.
+    pub fn new(durations: &[Duration]) -> Self {
+        let mut millis: Vec<u64> = durations.iter().map(|d| d.as_millis() as u64).collect();
+        millis.sort_unstable();
+
+        let rank = |percentile: usize| millis[(millis.len() * percentile).div_ceil(100).max(1) - 1];
+
+        Self {
+            min_millis: millis[0],
+            median_millis: rank(50),
+            p95_millis: rank(95),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MachineInfo {
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub cpu: Option<String>,
+    pub cpus: Option<usize>,
+    pub memory_bytes: Option<u64>,
+}
+
+impl MachineInfo {
+    /// The CPU model and memory are only detected on Linux.
+    pub fn detect() -> Self {
+        let cpu = fs::read_to_string("/proc/cpuinfo").ok().and_then(|cpuinfo| {
+            cpuinfo
+                .lines()
+                .find(|line| line.starts_with("model name"))
+                .and_then(|line| line.split_once(':'))
+                .map(|(_, model)| model.trim().to_owned())
+        });
+        let memory_bytes = read_kb_field("/proc/meminfo", "MemTotal:");
+
+        Self {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            cpu,
+            cpus: std::thread::available_parallelism().ok().map(Into::into),
+            memory_bytes,
+        }
+    }
+}
+
+/// Proves `fixture` `warmup + iterations` times, only the last `iterations` are measured.
+///
+/// Every measured iteration is also appended to the results CSV at `results`.
+pub fn run_scenario(
+    fixture: &Fixture,
+    warmup: usize,
+    iterations: usize,
+    results: &Path,
+) -> Result<ScenarioResult> {
+    ensure!(iterations > 0, "at least one iteration is required");
+
+    let opts = prover_opts()?;
+    let staged = ProverMode::current()?.backend != ProverBackend::Bonsai;
+    let mut durations = Vec::with_capacity(iterations);
+    let mut iteration_stages = Vec::with_capacity(iterations);
+    let mut peak_rss = None;
+    let mut stats = None;
+
+    for i in 0..warmup + iterations {
+        let request = fixture.request();
+        let start = Instant::now();
+        let (receipt, prove_stats, stages, run_peak_rss) = if staged {
+            let (info, peak_rss) = track_peak_rss(|| prove_request_staged(&request, &opts));
+            let info = info?;
+            (info.receipt, info.stats, Some(info.stages), peak_rss)
+        } else {
+            // Bonsai proves remotely, the memory of this process says nothing about proving.
+            let info = prove_request(&request, &opts)?;
+            (info.receipt, info.stats, None, None)
+        };
+        let duration = start.elapsed();
+
+        let valid = Guest::Default.committed_validity(&receipt.journal.bytes, &request)?;
+        ensure!(
+            valid == fixture.expected.valid,
+            "{}: guest committed {valid}, expected {}",
+            fixture.name,
+            fixture.expected.valid
+        );
+
+        if i < warmup {
+            continue;
+        }
+        let run_stats = Stats::from(&prove_stats);
+        log_results(results, &fixture.name, &run_stats, duration, run_peak_rss)?;
+        durations.push(duration);
+        peak_rss = peak_rss.max(run_peak_rss);
+        iteration_stages.extend(stages);
+        stats.get_or_insert(run_stats);
+    }
+
+    let stats = stats.expect("at least one iteration is measured; qed;");
+    Ok(ScenarioResult {
+        name: fixture.name.clone(),
+        summary: Summary::new(&durations),
+        segments: stats.segments,
+        total_cycles: stats.total_cycles,
+        user_cycles: stats.user_cycles,
+        durations_millis: durations.iter().map(|d| d.as_millis() as u64).collect(),
+        peak_rss_bytes: peak_rss,
+        stages: median_stages(&iteration_stages),
+        iteration_stages,
+    })
+}
+
+/// The median of every field, `None` if no stages were measured.
+fn median_stages(stages: &[Stages]) -> Option<Stages> {
+    fn median<T: Ord + Copy>(values: impl Iterator<Item = T>) -> Option<T> {
+        let mut values: Vec<_> = values.collect();
+        values.sort_unstable();
+        values.get(values.len().checked_sub(1)? / 2).copied()
+    }
+
+    Some(Stages {
+        execute_millis: median(stages.iter().map(|s| s.execute_millis))?,
+        prove_segments_millis: median(stages.iter().map(|s| s.prove_segments_millis))?,
+        lift_join_millis: median(stages.iter().filter_map(|s| s.lift_join_millis)),
+        groth16_millis: median(stages.iter().filter_map(|s| s.groth16_millis)),
+        composite_seal_bytes: median(stages.iter().map(|s| s.composite_seal_bytes))?,
+        succinct_seal_bytes: median(stages.iter().filter_map(|s| s.succinct_seal_bytes)),
+        groth16_seal_bytes: median(stages.iter().filter_map(|s| s.groth16_seal_bytes)),
+    })
+}
+
+pub fn run(fixtures: &[Fixture], warmup: usize, iterations: usize, results: &Path) -> Result<Report> {
+    let started_at = Utc::now().to_rfc3339();
+    let scenarios = fixtures
+        .iter()
+        .map(|fixture| run_scenario(fixture, warmup, iterations, results))
+        .collect::<Result<_>>()?;
+
+    Ok(Report {
+        started_at,
+        machine: MachineInfo::detect(),
+        prover: ProverMode::current()?,
+        warmup,
+        iterations,
+        scenarios,
+    })
+}
+
+impl Report {
+    pub fn write_json(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// One line per scenario, overwriting `path`. Stages that were not measured are left empty.
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut file = File::create(path)?;
+        writeln!(
+            file,
+            "started_at,prover,test,iterations,min_millis,median_millis,p95_millis,segments,total_cycles,user_cycles,peak_rss_bytes,\
+             execute_millis,prove_segments_millis,lift_join_millis,groth16_millis,\
+             composite_seal_bytes,succinct_seal_bytes,groth16_seal_bytes"
+        )?;
+        for scenario in &self.scenarios {
+            let stage = |f: fn(&Stages) -> Option<u64>| {
+                scenario
+                    .stages
+                    .as_ref()
+                    .and_then(f)
+                    .map(|value| value.to_string())
+                    .unwrap_or_default()
+            };
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                self.started_at,
+                self.prover,
+                scenario.name,
+                self.iterations,
+                scenario.summary.min_millis,
+                scenario.summary.median_millis,
+                scenario.summary.p95_millis,
+                scenario.segments,
+                scenario.total_cycles,
+                scenario.user_cycles,
+                scenario.peak_rss_bytes.map(|bytes| bytes.to_string()).unwrap_or_default(),
+                stage(|s| Some(s.execute_millis)),
+                stage(|s| Some(s.prove_segments_millis)),
+                stage(|s| s.lift_join_millis),
+                stage(|s| s.groth16_millis),
+                stage(|s| Some(s.composite_seal_bytes as u64)),
+                stage(|s| s.succinct_seal_bytes.map(|bytes| bytes as u64)),
+                stage(|s| s.groth16_seal_bytes.map(|bytes| bytes as u64)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{median_stages, Summary};
+    use crate::prove::Stages;
+
+    #[test]
+    fn test_summary() {
+        let durations: Vec<_> = (1..=20).rev().map(Duration::from_millis).collect();
+        assert_eq!(
+            Summary::new(&durations),
+            Summary {
+                min_millis: 1,
+                median_millis: 10,
+                p95_millis: 19
+            }
+        );
+
+        assert_eq!(
+            Summary::new(&[Duration::from_millis(7)]),
+            Summary {
+                min_millis: 7,
+                median_millis: 7,
+                p95_millis: 7
+            }
+        );
+    }
+
+    #[test]
+    fn test_median_stages() {
+        let stages = |millis: u64, groth16_millis: Option<u64>| Stages {
+            execute_millis: millis,
+            prove_segments_millis: millis * 10,
+            lift_join_millis: Some(millis * 2),
+            groth16_millis,
+            composite_seal_bytes: 1000,
+            succinct_seal_bytes: Some(200),
+            groth16_seal_bytes: groth16_millis.map(|_| 256),
+        };
+
+        assert_eq!(median_stages(&[]), None);
+        assert_eq!(
+            median_stages(&[stages(3, Some(30)), stages(1, None), stages(2, Some(10))]),
+            Some(Stages {
+                execute_millis: 2,
+                prove_segments_millis: 20,
+                lift_join_millis: Some(4),
+                groth16_millis: Some(10),
+                composite_seal_bytes: 1000,
+                succinct_seal_bytes: Some(200),
+                groth16_seal_bytes: Some(256),
+            })
+        );
+    }
+}
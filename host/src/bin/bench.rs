@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+
+use anyhow::{ensure, Result};
+use clap::Parser;
+use host::{
+    bench,
+    config::ConfigArgs,
+    fixtures::load_fixtures,
+    prove::Guest,
+    sweep,
+};
+
+/// Proves every fixture repeatedly and reports duration statistics and cycle counts.
+///
+/// The prover and the receipt kind are taken from the configuration, see `host::config`, or the
+/// same environment variables as in the tests (`RISC0_DEV_MODE`, `BONSAI_API_URL`/`BONSAI_API_KEY`
+/// and `SNARK_WRAPPING`).
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Defaults to `fixtures` in the configuration.
+    #[arg(long)]
+    fixtures: Option<PathBuf>,
+    /// Only run the fixture with this name.
+    #[arg(long)]
+    fixture: Option<String>,
+    /// Unmeasured iterations per scenario.
+    #[arg(long, default_value_t = 1)]
+    warmup: usize,
+    /// Measured iterations per scenario.
+    #[arg(long, short = 'n', default_value_t = 5)]
+    iterations: usize,
+    /// The results CSV every measured iteration is appended to, defaults to `results` in the
+    /// configuration.
+    #[arg(long)]
+    results: Option<PathBuf>,
+    /// The summary CSV, overwritten.
+    #[arg(long, default_value = "bench.csv")]
+    csv: PathBuf,
+    /// The full report, overwritten.
+    #[arg(long, default_value = "bench.json")]
+    json: PathBuf,
+    /// Sweep these segment sizes (`segment_limit_po2`) instead of running the benchmark.
+    #[arg(long, value_delimiter = ',')]
+    sweep_po2: Vec<u32>,
+    /// The guest builds to sweep.
+    #[arg(long, value_delimiter = ',', default_value = "default", requires = "sweep_po2")]
+    sweep_guest: Vec<Guest>,
+    /// The sweep summary CSV, overwritten.
+    #[arg(long, default_value = "sweep.csv")]
+    sweep_csv: PathBuf,
+    /// The full sweep report, overwritten.
+    #[arg(long, default_value = "sweep.json")]
+    sweep_json: PathBuf,
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
+        .init();
+
+    let cli = Cli::parse();
+    let config = cli.config.install()?;
+
+    let fixtures: Vec<_> = load_fixtures(cli.fixtures.as_ref().unwrap_or(&config.fixtures))?
+        .into_iter()
+        .filter(|fixture| cli.fixture.as_ref().map_or(true, |name| &fixture.name == name))
+        .collect();
+    ensure!(!fixtures.is_empty(), "no fixtures selected");
+
+    if !cli.sweep_po2.is_empty() {
+        return run_sweep(&cli, &fixtures);
+    }
+
+    let results = cli.results.as_ref().unwrap_or(&config.results);
+    let report = bench::run(&fixtures, cli.warmup, cli.iterations, results)?;
+
+    let optional = |value: Option<u64>| value.map(|value| value.to_string()).unwrap_or_else(|| "-".to_owned());
+
+    println!("| test                       |   min_millis | median_millis |   p95_millis | segments | total_cycles | user_cycles | peak_rss_bytes |");
+    println!("|----------------------------|-------------:|--------------:|-------------:|---------:|-------------:|------------:|---------------:|");
+    for scenario in &report.scenarios {
+        println!(
+            "| {:<26} | {:>12} | {:>13} | {:>12} | {:>8} | {:>12} | {:>11} | {:>14} |",
+            scenario.name,
+            scenario.summary.min_millis,
+            scenario.summary.median_millis,
+            scenario.summary.p95_millis,
+            scenario.segments,
+            scenario.total_cycles,
+            scenario.user_cycles,
+            optional(scenario.peak_rss_bytes)
+        );
+    }
+
+    if report.scenarios.iter().any(|scenario| scenario.stages.is_some()) {
+        println!();
+        println!("| test                       | execute_millis | prove_segments_millis | lift_join_millis | groth16_millis | composite_seal_bytes | succinct_seal_bytes | groth16_seal_bytes |");
+        println!("|----------------------------|---------------:|----------------------:|-----------------:|---------------:|---------------------:|--------------------:|-------------------:|");
+        for scenario in &report.scenarios {
+            let Some(stages) = &scenario.stages else {
+                continue;
+            };
+            println!(
+                "| {:<26} | {:>14} | {:>21} | {:>16} | {:>14} | {:>20} | {:>19} | {:>18} |",
+                scenario.name,
+                stages.execute_millis,
+                stages.prove_segments_millis,
+                optional(stages.lift_join_millis),
+                optional(stages.groth16_millis),
+                stages.composite_seal_bytes,
+                optional(stages.succinct_seal_bytes.map(|bytes| bytes as u64)),
+                optional(stages.groth16_seal_bytes.map(|bytes| bytes as u64)),
+            );
+        }
+    }
+
+    report.write_csv(&cli.csv)?;
+    report.write_json(&cli.json)?;
+
+    Ok(())
+}
+
+fn run_sweep(cli: &Cli, fixtures: &[host::fixtures::Fixture]) -> Result<()> {
+    let report = sweep::run(fixtures, &cli.sweep_guest, &cli.sweep_po2, cli.warmup, cli.iterations)?;
+
+    println!("| test                       | guest           | po2 | median_millis | segments | total_cycles | user_cycles | peak_rss_bytes |");
+    println!("|----------------------------|-----------------|----:|--------------:|---------:|-------------:|------------:|---------------:|");
+    for result in &report.results {
+        println!(
+            "| {:<26} | {:<15} | {:>3} | {:>13} | {:>8} | {:>12} | {:>11} | {:>14} |",
+            result.test,
+            result.guest,
+            result.segment_limit_po2,
+            result.summary.median_millis,
+            result.segments,
+            result.total_cycles,
+            result.user_cycles,
+            result
+                .peak_rss_bytes
+                .map(|bytes| bytes.to_string())
+                .unwrap_or_else(|| "-".to_owned())
+        );
+    }
+
+    println!();
+    for best in report.fastest() {
+        println!(
+            "{}: fastest with {} guest, po2 {} ({} ms)",
+            best.test, best.guest, best.segment_limit_po2, best.summary.median_millis
+        );
+    }
+
+    report.write_csv(&cli.sweep_csv)?;
+    report.write_json(&cli.sweep_json)?;
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::{sync::Arc, thread};
+
+use anyhow::Result;
+use clap::Parser;
+use host::{http, mock_bonsai::MockBonsai};
+
+/// Serves the Bonsai endpoints used by `BonsaiProver`, proving with the local prover.
+///
+/// Point the host at it with `--prover bonsai --bonsai-url http://<addr> --bonsai-api-key <any>`.
+#[derive(Parser)]
+struct Cli {
+    #[arg(long, default_value = "127.0.0.1:8081")]
+    addr: String,
+    /// Return fake receipts. They only verify in dev mode, which `--prover bonsai` turns off, so
+    /// this is for clients using `BonsaiProver` directly.
+    #[arg(long)]
+    dev: bool,
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
+        .init();
+
+    let cli = Cli::parse();
+    std::env::set_var("RISC0_DEV_MODE", if cli.dev { "1" } else { "0" });
+
+    let server = http::bind(&cli.addr)?;
+    let url = format!("http://{}", server.server_addr());
+    let bonsai = Arc::new(MockBonsai::new(&url));
+    {
+        let bonsai = bonsai.clone();
+        thread::spawn(move || bonsai.work());
+    }
+
+    println!("listening on {url}");
+    http::serve(&server, |method, url, body| {
+        bonsai.handle(method, url, body)
+    });
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::{path::PathBuf, sync::Arc, thread};
+
+use anyhow::{ensure, Result};
+use clap::Parser;
+use host::{
+    cache::{ProofCache, DEFAULT_MAX_BYTES},
+    config::ConfigArgs,
+    http,
+    service::{JobStore, Service},
+};
+
+/// Proves `VerifyZkpRequest`s submitted over HTTP, see `host::service` for the endpoints.
+///
+/// The prover and the receipt kind are taken from the configuration, see `host::config`, or the
+/// same environment variables as in the tests (`RISC0_DEV_MODE`, `BONSAI_API_URL`/`BONSAI_API_KEY`
+/// and `SNARK_WRAPPING`).
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: String,
+    /// Jobs proven concurrently. Local proving uses all cores and a lot of memory per job.
+    #[arg(long, default_value_t = 1)]
+    workers: usize,
+    /// The directory jobs and receipts are kept in.
+    #[arg(long, default_value = "jobs")]
+    store: PathBuf,
+    /// Answer requests that were proven before from the receipts cached in this directory.
+    #[arg(long)]
+    cache: Option<PathBuf>,
+    /// The least recently used receipts are evicted beyond this size.
+    #[arg(long, default_value_t = DEFAULT_MAX_BYTES, requires = "cache")]
+    cache_max_bytes: u64,
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
+        .init();
+
+    let cli = Cli::parse();
+    cli.config.install()?;
+    ensure!(cli.workers > 0, "at least one worker is required");
+
+    let cache = cli
+        .cache
+        .map(|dir| ProofCache::open(dir, cli.cache_max_bytes))
+        .transpose()?;
+    let service = Arc::new(Service::open(JobStore::open(&cli.store)?, cache)?);
+    for _ in 0..cli.workers {
+        let service = service.clone();
+        thread::spawn(move || service.work());
+    }
+
+    let server = http::bind(&cli.addr)?;
+    println!("listening on http://{}", cli.addr);
+    http::serve(&server, |method, url, body| service.handle(method, url, body));
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! A content-addressed cache of receipts on disk, so identical requests are only proven once.
+//!
+//! Entries are keyed by the hash of the image ID, the serialized request, the prover options and
+//! whether `RISC0_DEV_MODE` is set, so fake receipts never answer for real ones. They are verified
+//! against the image ID when loaded. The least recently used entries are evicted once the cache
+//! grows beyond its size limit.
+
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use cometbls_groth16_verifier::VerifyZkpRequest;
+use risc0_zkvm::{ProverOpts, Receipt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::results::Stats;
+
+/// 1 GiB.
+pub const DEFAULT_MAX_BYTES: u64 = 1 << 30;
+
+pub struct ProofCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    receipt: Receipt,
+    stats: Stats,
+}
+
+impl ProofCache {
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).with_context(|| format!("unable to create {}", dir.display()))?;
+        Ok(Self { dir, max_bytes })
+    }
+
+    /// The hex encoded SHA-256 of `(image_id, request, opts, dev_mode)`, bincode encoded.
+    pub fn key(
+        image_id: [u32; 8],
+        request: &VerifyZkpRequest,
+        opts: &ProverOpts,
+        dev_mode: bool,
+    ) -> Result<String> {
+        let bytes = bincode::serialize(&(image_id, request, opts, dev_mode))?;
+        Ok(hex::encode(Sha256::digest(bytes)))
+    }
+
+    /// The cached receipt for `key`, if there is one that verifies against `image_id`.
+    ///
+    /// Entries that cannot be decoded or do not verify are removed.
+    pub fn get(&self, key: &str, image_id: [u32; 8]) -> Result<Option<(Receipt, Stats)>> {
+        let path = self.path(key);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).with_context(|| format!("unable to read {}", path.display())),
+        };
+
+        let entry = bincode::deserialize::<Entry>(&bytes)
+            .context("unable to decode")
+            .and_then(|entry| {
+                entry.receipt.verify(image_id).context("receipt does not verify")?;
+                Ok(entry)
+            });
+        match entry {
+            Ok(entry) => {
+                // The modification time tracks the last use for eviction.
+                File::options()
+                    .write(true)
+                    .open(&path)
+                    .and_then(|file| file.set_modified(SystemTime::now()))
+                    .with_context(|| format!("unable to touch {}", path.display()))?;
+                Ok(Some((entry.receipt, entry.stats)))
+            }
+            Err(err) => {
+                eprintln!("removing cached receipt {}: {err:#}", path.display());
+                fs::remove_file(&path)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Writes the entry for `key` atomically.
+    ///
+    /// The temporary file is unique to the process and the call, so concurrent writers of the
+    /// same key never interleave, and it is ignored by [`Self::evict`].
+    pub fn put(&self, key: &str, receipt: &Receipt, stats: &Stats) -> Result<()> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let path = self.path(key);
+        let tmp = self.dir.join(format!(
+            "{key}.{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp, bincode::serialize(&Entry { receipt: receipt.clone(), stats: *stats })?)
+            .with_context(|| format!("unable to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("unable to write {}", path.display()))?;
+
+        self.evict()
+    }
+
+    /// Removes the least recently used entries until the cache fits into its size limit.
+    pub fn evict(&self) -> Result<()> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if entry.path().extension().is_some_and(|ext| ext == "receipt") {
+                let metadata = entry.metadata()?;
+                entries.push((metadata.modified()?, metadata.len(), entry.path()));
+            }
+        }
+        entries.sort();
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path).with_context(|| format!("unable to remove {}", path.display()))?;
+            total -= len;
+        }
+        Ok(())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.receipt"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::fixtures::{default_fixture_dir, load_fixtures};
+
+    fn cache(name: &str, max_bytes: u64) -> ProofCache {
+        let dir = std::env::temp_dir().join(format!("cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        ProofCache::open(dir, max_bytes).unwrap()
+    }
+
+    #[test]
+    fn test_key() {
+        let fixtures = load_fixtures(default_fixture_dir()).unwrap();
+        let (a, b) = (fixtures[0].request(), fixtures[1].request());
+        let opts = ProverOpts::default();
+
+        let key = ProofCache::key([1; 8], &a, &opts, false).unwrap();
+        assert_eq!(key, ProofCache::key([1; 8], &fixtures[0].request(), &opts, false).unwrap());
+        assert_ne!(key, ProofCache::key([2; 8], &a, &opts, false).unwrap());
+        assert_ne!(key, ProofCache::key([1; 8], &b, &opts, false).unwrap());
+        assert_ne!(key, ProofCache::key([1; 8], &a, &ProverOpts::groth16(), false).unwrap());
+        assert_ne!(key, ProofCache::key([1; 8], &a, &opts, true).unwrap());
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_removed() {
+        let cache = cache("corrupt", DEFAULT_MAX_BYTES);
+        fs::write(cache.path("corrupt"), b"not a receipt").unwrap();
+
+        assert!(cache.get("corrupt", [0; 8]).unwrap().is_none());
+        assert!(!cache.path("corrupt").exists());
+        assert!(cache.get("missing", [0; 8]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let cache = cache("evict", 25);
+        let now = SystemTime::now();
+        for (i, key) in ["old", "used", "new"].into_iter().enumerate() {
+            fs::write(cache.path(key), [0; 10]).unwrap();
+            File::options()
+                .write(true)
+                .open(cache.path(key))
+                .unwrap()
+                .set_modified(now - Duration::from_secs(60 * (3 - i as u64)))
+                .unwrap();
+        }
+
+        cache.evict().unwrap();
+
+        assert!(!cache.path("old").exists());
+        assert!(cache.path("used").exists());
+        assert!(cache.path("new").exists());
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use cometbls_groth16_verifier::{handle_verify_zkp_request, Error};
+use risc0_zkvm::ProverOpts;
+
+use crate::{
+    fixtures::Fixture,
+    prove::{prove_request, Guest},
+};
+
+/// The result of a fixture verified natively and in the zkVM.
+#[derive(Debug)]
+pub struct Comparison {
+    pub name: String,
+    pub expected: bool,
+    pub native: Result<(), Error>,
+    pub zkvm: bool,
+}
+
+impl Comparison {
+    /// Whether the guest committed the same outcome as the native run.
+    pub fn is_identical(&self) -> bool {
+        self.native.is_ok() == self.zkvm
+    }
+}
+
+pub fn compare(fixture: &Fixture) -> Result<Comparison> {
+    let native = handle_verify_zkp_request(fixture.request());
+
+    let request = fixture.request();
+    let prove_info = prove_request(&request, &ProverOpts::default())?;
+    let zkvm = Guest::Default.committed_validity(&prove_info.receipt.journal.bytes, &request)?;
+
+    Ok(Comparison {
+        name: fixture.name.clone(),
+        expected: fixture.expected.valid,
+        native,
+        zkvm,
+    })
+}
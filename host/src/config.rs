@@ -0,0 +1,387 @@
+//! The host configuration, shared by the CLI, the benchmark and the service.
+//!
+//! Settings are read from a TOML file (`host.toml` in the working directory if it exists, or the
+//! file given with `--config` or `HOST_CONFIG`), then overridden by environment variables and
+//! finally by command line flags:
+//!
+//! ```toml
+//! prover = "bonsai"              # HOST_PROVER: local, dev or bonsai
+//! bonsai_url = "http://127.0.0.1:8081"  # BONSAI_API_URL
+//! bonsai_api_key = "..."         # BONSAI_API_KEY
+//! receipt_kind = "groth16"       # HOST_RECEIPT_KIND: composite, succinct or groth16
+//! segment_limit_po2 = 20         # HOST_SEGMENT_PO2
+//! profile_dir = "profiles"       # HOST_PROFILE_DIR
+//! results = "out.csv"            # HOST_RESULTS
+//! fixtures = "fixtures"          # HOST_FIXTURES
+//! ```
+//!
+//! Without a prover, it is selected through `RISC0_DEV_MODE` and `BONSAI_API_URL` as before, and
+//! `SNARK_WRAPPING=1` still selects Groth16 receipts.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use risc0_zkvm::ProverOpts;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fixtures::default_fixture_dir,
+    prove::{select_backend, ProverArgs, ProverBackend},
+};
+
+/// Read from the working directory when no other file is given.
+pub const DEFAULT_CONFIG: &str = "host.toml";
+
+/// The segment sizes risc0-zkvm 1.1 supports, as `log2` of the cycles.
+pub const SEGMENT_LIMIT_PO2: std::ops::RangeInclusive<u32> = 13..=24;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The kind of receipt to prove.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptKind {
+    /// A receipt per segment.
+    Composite,
+    /// The segments lifted and joined into a single receipt.
+    Succinct,
+    /// The succinct receipt wrapped into Groth16, verifiable on Ethereum.
+    Groth16,
+}
+
+impl ReceiptKind {
+    pub fn prover_opts(self) -> ProverOpts {
+        match self {
+            ReceiptKind::Composite => ProverOpts::composite(),
+            ReceiptKind::Succinct => ProverOpts::succinct(),
+            ReceiptKind::Groth16 => ProverOpts::groth16(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// `None` selects the prover through the risc0-zkvm environment variables.
+    pub prover: Option<ProverBackend>,
+    pub bonsai_url: Option<String>,
+    pub bonsai_api_key: Option<String>,
+    pub receipt_kind: ReceiptKind,
+    /// The risc0-zkvm default (20) if not set.
+    pub segment_limit_po2: Option<u32>,
+    /// Where guest profiles are written.
+    pub profile_dir: PathBuf,
+    /// The results CSV.
+    pub results: PathBuf,
+    pub fixtures: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            prover: None,
+            bonsai_url: None,
+            bonsai_api_key: None,
+            receipt_kind: ReceiptKind::Composite,
+            segment_limit_po2: None,
+            profile_dir: PathBuf::from("."),
+            results: PathBuf::from("out.csv"),
+            fixtures: default_fixture_dir(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads `path`, or [`DEFAULT_CONFIG`] if it exists, and applies the environment overrides.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = path
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os("HOST_CONFIG").map(PathBuf::from))
+            .or_else(|| Some(PathBuf::from(DEFAULT_CONFIG)).filter(|path| path.exists()));
+        let config = match path {
+            Some(path) => {
+                let toml = fs::read_to_string(&path)
+                    .with_context(|| format!("unable to read {}", path.display()))?;
+                Self::parse(&toml).with_context(|| format!("invalid config {}", path.display()))?
+            }
+            None => Self::default(),
+        };
+
+        config.with_env(|name| std::env::var(name).ok())
+    }
+
+    pub fn parse(toml: &str) -> Result<Self> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Applies the overrides `var` returns for the environment variables in the module docs.
+    pub fn with_env(mut self, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        if let Some(prover) = var("HOST_PROVER") {
+            self.prover = Some(parse_value("HOST_PROVER", &prover)?);
+        }
+        if let Some(url) = var("BONSAI_API_URL") {
+            self.bonsai_url = Some(url);
+        }
+        if let Some(api_key) = var("BONSAI_API_KEY") {
+            self.bonsai_api_key = Some(api_key);
+        }
+        if let Some(receipt_kind) = var("HOST_RECEIPT_KIND") {
+            self.receipt_kind = parse_value("HOST_RECEIPT_KIND", &receipt_kind)?;
+        } else if var("SNARK_WRAPPING")
+            .is_some_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"))
+        {
+            self.receipt_kind = ReceiptKind::Groth16;
+        }
+        if let Some(po2) = var("HOST_SEGMENT_PO2") {
+            self.segment_limit_po2 = Some(
+                po2.parse()
+                    .with_context(|| format!("HOST_SEGMENT_PO2: invalid number {po2:?}"))?,
+            );
+        }
+        if let Some(dir) = var("HOST_PROFILE_DIR") {
+            self.profile_dir = dir.into();
+        }
+        if let Some(results) = var("HOST_RESULTS") {
+            self.results = results.into();
+        }
+        if let Some(fixtures) = var("HOST_FIXTURES") {
+            self.fixtures = fixtures.into();
+        }
+        Ok(self)
+    }
+
+    /// Applies the prover flags, which take precedence over the file and the environment.
+    pub fn with_args(mut self, args: &ProverArgs) -> Self {
+        if args.prover.is_some() {
+            self.prover = args.prover;
+        }
+        if args.bonsai_url.is_some() {
+            self.bonsai_url = args.bonsai_url.clone();
+        }
+        if args.bonsai_api_key.is_some() {
+            self.bonsai_api_key = args.bonsai_api_key.clone();
+        }
+        if let Some(receipt_kind) = args.receipt_kind {
+            self.receipt_kind = receipt_kind;
+        }
+        if args.segment_limit_po2.is_some() {
+            self.segment_limit_po2 = args.segment_limit_po2;
+        }
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(po2) = self.segment_limit_po2 {
+            ensure!(
+                SEGMENT_LIMIT_PO2.contains(&po2),
+                "segment_limit_po2 must be within {}..={}, not {po2}",
+                SEGMENT_LIMIT_PO2.start(),
+                SEGMENT_LIMIT_PO2.end()
+            );
+        }
+        if self.prover == Some(ProverBackend::Bonsai) {
+            let Some(url) = &self.bonsai_url else {
+                bail!("the bonsai prover needs bonsai_url (BONSAI_API_URL, --bonsai-url)");
+            };
+            ensure!(
+                url.starts_with("http://") || url.starts_with("https://"),
+                "bonsai_url must be an http(s) URL, not {url:?}"
+            );
+            ensure!(
+                self.bonsai_api_key.is_some(),
+                "the bonsai prover needs bonsai_api_key (BONSAI_API_KEY, --bonsai-api-key)"
+            );
+        }
+        ensure!(
+            !self.profile_dir.is_file(),
+            "profile_dir {} is a file",
+            self.profile_dir.display()
+        );
+        ensure!(
+            !self.results.is_dir(),
+            "results {} is a directory",
+            self.results.display()
+        );
+        Ok(())
+    }
+
+    /// Validates the configuration, selects the prover for the rest of the process and makes it
+    /// the [`current`] configuration. Call this before spawning threads.
+    pub fn install(mut self) -> Result<&'static Config> {
+        self.validate()?;
+        let backend = self.prover.unwrap_or_else(ProverBackend::from_env);
+        select_backend(
+            backend,
+            self.bonsai_url.as_deref(),
+            self.bonsai_api_key.as_deref(),
+        )?;
+        self.prover = Some(backend);
+
+        CONFIG
+            .set(self)
+            .map_err(|_| anyhow::anyhow!("the configuration is already installed"))?;
+        Ok(CONFIG.get().expect("just installed; qed;"))
+    }
+
+    pub fn prover_opts(&self) -> ProverOpts {
+        self.receipt_kind.prover_opts()
+    }
+}
+
+/// The installed configuration, or the one from the default file and the environment, which
+/// fails if it is invalid. Unlike [`Config::install`], the latter does not select the prover.
+pub fn current() -> Result<&'static Config> {
+    if let Some(config) = CONFIG.get() {
+        return Ok(config);
+    }
+    let config = Config::load(None)?;
+    config.validate()?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+fn parse_value<T: clap::ValueEnum>(var: &str, value: &str) -> Result<T> {
+    T::from_str(value, true).map_err(|_| {
+        let values: Vec<_> = T::value_variants()
+            .iter()
+            .filter_map(|variant| variant.to_possible_value())
+            .map(|value| value.get_name().to_owned())
+            .collect();
+        anyhow::anyhow!(
+            "{var}: invalid value {value:?}, expected one of {}",
+            values.join(", ")
+        )
+    })
+}
+
+/// The configuration file and the flags overriding it, for every binary.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct ConfigArgs {
+    /// Defaults to `HOST_CONFIG`, or `host.toml` if it exists.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    #[command(flatten)]
+    pub prover: ProverArgs,
+}
+
+impl ConfigArgs {
+    /// Loads the configuration, applies the flags and installs it.
+    pub fn install(&self) -> Result<&'static Config> {
+        Config::load(self.config.as_deref())?
+            .with_args(&self.prover)
+            .install()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_default() {
+        let config = Config::default();
+        assert_eq!(config.receipt_kind, ReceiptKind::Composite);
+        assert_eq!(
+            config.prover_opts().receipt_kind,
+            ProverOpts::default().receipt_kind
+        );
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r#"
+            prover = "bonsai"
+            bonsai_url = "http://127.0.0.1:8081"
+            bonsai_api_key = "key"
+            receipt_kind = "composite"
+            segment_limit_po2 = 19
+            results = "results.csv"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.prover, Some(ProverBackend::Bonsai));
+        assert_eq!(config.receipt_kind, ReceiptKind::Composite);
+        assert_eq!(config.segment_limit_po2, Some(19));
+        assert_eq!(config.results, Path::new("results.csv"));
+        assert_eq!(config.fixtures, default_fixture_dir());
+        config.validate().unwrap();
+
+        assert!(Config::parse("segment_po2 = 19").is_err());
+        assert!(Config::parse(r#"receipt_kind = "stark""#).is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let config = Config::parse(r#"receipt_kind = "composite""#)
+            .unwrap()
+            .with_env(env(&[
+                ("HOST_PROVER", "dev"),
+                ("HOST_SEGMENT_PO2", "18"),
+                ("HOST_FIXTURES", "other"),
+            ]))
+            .unwrap();
+        assert_eq!(config.prover, Some(ProverBackend::Dev));
+        assert_eq!(config.receipt_kind, ReceiptKind::Composite);
+        assert_eq!(config.segment_limit_po2, Some(18));
+        assert_eq!(config.fixtures, Path::new("other"));
+
+        let config = Config::default()
+            .with_env(env(&[("SNARK_WRAPPING", "1")]))
+            .unwrap();
+        assert_eq!(config.receipt_kind, ReceiptKind::Groth16);
+        let config = Config::default()
+            .with_env(env(&[
+                ("SNARK_WRAPPING", "1"),
+                ("HOST_RECEIPT_KIND", "succinct"),
+            ]))
+            .unwrap();
+        assert_eq!(config.receipt_kind, ReceiptKind::Succinct);
+
+        let err = Config::default()
+            .with_env(env(&[("HOST_PROVER", "gpu")]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"HOST_PROVER: invalid value "gpu", expected one of local, dev, bonsai"#
+        );
+        assert!(Config::default()
+            .with_env(env(&[("HOST_SEGMENT_PO2", "x")]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = Config {
+            segment_limit_po2: Some(25),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = Config {
+            prover: Some(ProverBackend::Bonsai),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+        let config = Config {
+            bonsai_url: Some("127.0.0.1:8081".to_owned()),
+            bonsai_api_key: Some("key".to_owned()),
+            ..config
+        };
+        assert!(config.validate().is_err());
+    }
+}
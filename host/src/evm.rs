@@ -0,0 +1,173 @@
+//! Calldata for verifying receipts on Ethereum, through the RISC Zero verifier router:
+//! `verify(bytes seal, bytes32 imageId, bytes32 journalDigest)`.
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use cometbls_groth16_verifier::{abi::VerifiedHeader, VerifyZkpRequest};
+use ethabi::{ParamType, Token};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    InnerReceipt, Receipt,
+};
+use serde::Serialize;
+
+use crate::{inspect::DecodedJournal, prove::Guest};
+
+/// The arguments of `IRiscZeroVerifier.verify` and the calldata calling it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifierCalldata {
+    #[serde(with = "hex_0x")]
+    pub seal: Vec<u8>,
+    #[serde(with = "hex_0x")]
+    pub image_id: [u8; 32],
+    #[serde(with = "hex_0x")]
+    pub journal_digest: [u8; 32],
+    /// The function selector followed by the ABI encoded arguments.
+    #[serde(with = "hex_0x")]
+    pub calldata: Vec<u8>,
+}
+
+/// The seal as the verifier router expects it: the first four bytes of the verifier parameters
+/// digest, selecting the verifier, followed by the Groth16 seal.
+///
+/// Fake receipts (dev mode) are encoded like `RiscZeroMockVerifier` expects them, `0xffffffff`
+/// followed by the claim digest.
+pub fn encode_seal(receipt: &Receipt) -> Result<Vec<u8>> {
+    match &receipt.inner {
+        InnerReceipt::Groth16(receipt) => {
+            let mut seal = receipt.verifier_parameters.as_bytes()[..4].to_vec();
+            seal.extend_from_slice(&receipt.seal);
+            Ok(seal)
+        }
+        InnerReceipt::Fake(receipt) => {
+            let mut seal = vec![0xff; 4];
+            seal.extend_from_slice(receipt.claim.digest().as_bytes());
+            Ok(seal)
+        }
+        _ => bail!("only Groth16 receipts can be verified on Ethereum, prove with SNARK_WRAPPING=1"),
+    }
+}
+
+impl VerifierCalldata {
+    pub fn new(receipt: &Receipt, image_id: impl Into<Digest>) -> Result<Self> {
+        let seal = encode_seal(receipt)?;
+        let image_id = bytes32(image_id.into());
+        let journal_digest = bytes32(receipt.journal.bytes.digest());
+
+        let selector = ethabi::short_signature(
+            "verify",
+            &[ParamType::Bytes, ParamType::FixedBytes(32), ParamType::FixedBytes(32)],
+        );
+        let mut calldata = selector.to_vec();
+        calldata.extend(ethabi::encode(&[
+            Token::Bytes(seal.clone()),
+            Token::FixedBytes(image_id.to_vec()),
+            Token::FixedBytes(journal_digest.to_vec()),
+        ]));
+
+        Ok(Self {
+            seal,
+            image_id,
+            journal_digest,
+            calldata,
+        })
+    }
+}
+
+/// The ABI encoded header a receipt of the verifier `guest` proves, if the guest committed that
+/// it is valid.
+///
+/// The ABI journal is the header itself. The serde journal only commits the digest of the
+/// request, so the header is encoded from `request`, which must be the request with that digest;
+/// any other request is rejected rather than printed as proven.
+pub fn verified_header(
+    receipt: &Receipt,
+    guest: Guest,
+    request: Option<&VerifyZkpRequest>,
+) -> Result<Vec<u8>> {
+    let journal = &receipt.journal.bytes;
+    match DecodedJournal::decode_verifier(journal, guest.journal_encoding())? {
+        DecodedJournal::Header(Some(_)) => Ok(journal.clone()),
+        DecodedJournal::Valid {
+            request_digest,
+            valid: true,
+        } => {
+            let request = request.context(
+                "the journal only commits the digest of the request, the request is needed to \
+                 encode its header",
+            )?;
+            ensure!(
+                request.digest() == request_digest,
+                "the receipt proves another request (digest 0x{}) than the given one (0x{})",
+                hex::encode(request_digest),
+                hex::encode(request.digest())
+            );
+            let header = VerifiedHeader::try_from(request)
+                .map_err(|err| anyhow!("the request has no valid header: {err:?}"))?;
+            Ok(header.abi_encode())
+        }
+        _ => bail!("the receipt proves that the request is invalid"),
+    }
+}
+
+fn bytes32(digest: Digest) -> [u8; 32] {
+    digest
+        .as_bytes()
+        .try_into()
+        .expect("digests are 32 bytes; qed;")
+}
+
+/// `0x` prefixed hex, as Ethereum tooling expects it.
+mod hex_0x {
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(bytes: impl AsRef<[u8]>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risc0_zkvm::{FakeReceipt, ReceiptClaim};
+
+    use super::*;
+    use crate::fixtures::{default_fixture_dir, load_fixture};
+
+    fn fake_receipt(guest: Guest, journal: Vec<u8>) -> Receipt {
+        let claim = ReceiptClaim::ok(guest.image_id(), journal.clone());
+        Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal)
+    }
+
+    #[test]
+    fn test_verified_header() {
+        let dir = default_fixture_dir();
+        let request = load_fixture(dir.join("valid_block_969001.json")).unwrap().request();
+        let other = load_fixture(dir.join("valid_block_969002.json")).unwrap().request();
+        let journal = risc0_zkvm::serde::to_vec(&(request.digest(), true)).unwrap();
+        let receipt = fake_receipt(Guest::Default, words_to_bytes(&journal));
+
+        let header = VerifiedHeader::try_from(&request).unwrap().abi_encode();
+        assert_eq!(verified_header(&receipt, Guest::Default, Some(&request)).unwrap(), header);
+        // The header of a request the receipt was not proven for is never printed.
+        assert!(verified_header(&receipt, Guest::Default, Some(&other)).is_err());
+        assert!(verified_header(&receipt, Guest::Default, None).is_err());
+
+        let receipt = fake_receipt(Guest::AbiJournal, header.clone());
+        assert_eq!(verified_header(&receipt, Guest::AbiJournal, None).unwrap(), header);
+    }
+
+    fn words_to_bytes(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_verify_selector() {
+        // `cast sig "verify(bytes,bytes32,bytes32)"`
+        assert_eq!(
+            ethabi::short_signature(
+                "verify",
+                &[ParamType::Bytes, ParamType::FixedBytes(32), ParamType::FixedBytes(32)],
+            ),
+            [0xab, 0x75, 0x0e, 0x75]
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use cometbls_groth16_verifier::{plonk::VerifyPlonkRequest, VerifyZkpRequest};
+use methods::{GUEST_CODE_FOR_ZK_PROOF_ELF, GUEST_CODE_FOR_ZK_PROOF_PLONK_ELF};
+use risc0_zkvm::{default_executor, SessionInfo};
+
+use crate::prove::env_builder;
+
+/// Runs the guest on `request` without proving.
+///
+/// The segments and cycle counts are the ones a prover would produce for the same request.
+pub fn execute_request(request: &VerifyZkpRequest) -> Result<(SessionInfo, Duration)> {
+    let env = env_builder()?.write(request)?.build()?;
+
+    let start = Instant::now();
+    let session = default_executor().execute(env, GUEST_CODE_FOR_ZK_PROOF_ELF)?;
+
+    Ok((session, start.elapsed()))
+}
+
+/// Runs the PLONK guest on `request` without proving, returning whether it accepted the proof.
+pub fn execute_plonk_request(
+    request: &VerifyPlonkRequest,
+) -> Result<(SessionInfo, Duration, bool)> {
+    let env = env_builder()?.write(request)?.build()?;
+
+    let start = Instant::now();
+    let session = default_executor().execute(env, GUEST_CODE_FOR_ZK_PROOF_PLONK_ELF)?;
+    let duration = start.elapsed();
+
+    let valid = session.journal.decode()?;
+    Ok((session, duration, valid))
+}
@@ -0,0 +1,117 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use cometbls_groth16_verifier::{compressed::compress_zkp, RawZKP, VerifyZkpRequest};
+use serde::{Deserialize, Serialize};
+
+/// The fixture directory shipped with the repository.
+pub fn default_fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures")
+}
+
+/// A test vector: a request for the guest and the outcome it must produce.
+///
+/// Fixtures are stored as `<name>.json`, the name is taken from the file stem.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Fixture {
+    #[serde(skip)]
+    pub name: String,
+    pub description: String,
+    pub request: FixtureRequest,
+    pub expected: Expected,
+}
+
+/// [`VerifyZkpRequest`] with hex encoded hashes and zkp.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FixtureRequest {
+    pub chain_id: String,
+    #[serde(with = "hex")]
+    pub trusted_validators_hash: [u8; 32],
+    pub height: i64,
+    pub seconds: i64,
+    pub nanos: i32,
+    #[serde(with = "hex")]
+    pub validators_hash: [u8; 32],
+    #[serde(with = "hex")]
+    pub next_validators_hash: [u8; 32],
+    #[serde(with = "hex")]
+    pub app_hash: [u8; 32],
+    #[serde(with = "hex")]
+    pub zkp: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Expected {
+    /// Whether `handle_verify_zkp_request` accepts the request.
+    pub valid: bool,
+    /// The `Debug` name of the `cometbls_groth16_verifier::Error` variant for invalid requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Fixture {
+    pub fn request(&self) -> VerifyZkpRequest {
+        self.request.clone().into()
+    }
+
+    /// [`Fixture::request`] with gnark's compressed points, `None` if the proof has invalid ones.
+    pub fn compressed_request(&self) -> Option<VerifyZkpRequest> {
+        let mut request = self.request();
+        let raw = RawZKP::try_from(&request.zkp[..]).ok()?;
+        request.zkp = compress_zkp(&raw).ok()?.to_vec();
+        Some(request)
+    }
+}
+
+impl From<FixtureRequest> for VerifyZkpRequest {
+    fn from(request: FixtureRequest) -> Self {
+        VerifyZkpRequest {
+            chain_id: request.chain_id,
+            trusted_validators_hash: request.trusted_validators_hash,
+            height: request.height,
+            validators_hash: request.validators_hash,
+            next_validators_hash: request.next_validators_hash,
+            app_hash: request.app_hash,
+            seconds: request.seconds,
+            nanos: request.nanos,
+            zkp: request.zkp,
+        }
+    }
+}
+
+pub fn load_fixture(path: impl AsRef<Path>) -> Result<Fixture> {
+    let path = path.as_ref();
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .with_context(|| format!("invalid fixture path {}", path.display()))?
+        .to_owned();
+    let contents =
+        fs::read(path).with_context(|| format!("unable to read fixture {}", path.display()))?;
+    let mut fixture: Fixture = serde_json::from_slice(&contents)
+        .with_context(|| format!("unable to parse fixture {}", path.display()))?;
+    fixture.name = name;
+    if !fixture.expected.valid && fixture.expected.error.is_none() {
+        anyhow::bail!("fixture {} is invalid but has no expected error", fixture.name);
+    }
+    Ok(fixture)
+}
+
+/// Loads every `*.json` fixture in `dir`, sorted by name.
+pub fn load_fixtures(dir: impl AsRef<Path>) -> Result<Vec<Fixture>> {
+    let dir = dir.as_ref();
+    let mut fixtures = fs::read_dir(dir)
+        .with_context(|| format!("unable to read fixture dir {}", dir.display()))?
+        .map(|entry| -> Result<PathBuf> { Ok(entry?.path()) })
+        .filter(|path| {
+            path.as_ref()
+                .map_or(true, |path| path.extension().is_some_and(|ext| ext == "json"))
+        })
+        .map(|path| load_fixture(path?))
+        .collect::<Result<Vec<_>>>()?;
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
@@ -0,0 +1,78 @@
+//! The HTTP plumbing shared by the proving service and the mock Bonsai server, which only
+//! implement [`Response`] returning handlers.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use tiny_http::{Header, Server};
+
+/// An HTTP response, independent of the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &impl Serialize) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec(value).expect("responses are serializable; qed;"),
+        }
+    }
+
+    pub fn bytes(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body,
+        }
+    }
+
+    pub fn empty(status: u16) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: Vec::new(),
+        }
+    }
+
+    pub fn error(status: u16, error: impl std::fmt::Display) -> Self {
+        Self::json(status, &serde_json::json!({ "error": error.to_string() }))
+    }
+}
+
+/// The path segments of `url`, without the query.
+pub fn path_segments(url: &str) -> Vec<&str> {
+    url.split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+pub fn bind(addr: &str) -> Result<Server> {
+    Server::http(addr).map_err(|err| anyhow!("unable to bind {addr}: {err}"))
+}
+
+/// Answers every request of `server` with `handle(method, url, body)`, one at a time.
+pub fn serve(server: &Server, handle: impl Fn(&str, &str, &[u8]) -> Response) {
+    for mut request in server.incoming_requests() {
+        let mut body = Vec::new();
+        let response = match request.as_reader().read_to_end(&mut body) {
+            Ok(_) => handle(request.method().as_str(), request.url(), &body),
+            Err(err) => Response::error(400, err),
+        };
+        let content_type = Header::from_bytes("Content-Type", response.content_type)
+            .expect("content types are valid headers; qed;");
+        if let Err(err) = request.respond(
+            tiny_http::Response::from_data(response.body)
+                .with_status_code(response.status)
+                .with_header(content_type),
+        ) {
+            eprintln!("unable to respond: {err}");
+        }
+    }
+}
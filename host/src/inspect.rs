@@ -0,0 +1,329 @@
+//! A readable summary of a receipt, instead of the `Debug` dump, which is unreadable for large
+//! composite receipts.
+
+use std::{fmt, fs, path::Path};
+
+use anyhow::{Context, Result};
+use cometbls_groth16_verifier::abi::VerifiedHeader;
+use methods::{
+    GUEST_CODE_FOR_ZK_PROOF_ABI_ID, GUEST_CODE_FOR_ZK_PROOF_ACCELERATED_ID,
+    GUEST_CODE_FOR_ZK_PROOF_AGGREGATE_ID, GUEST_CODE_FOR_ZK_PROOF_BATCH_ID,
+    GUEST_CODE_FOR_ZK_PROOF_ID, GUEST_CODE_FOR_ZK_PROOF_PLONK_ID,
+};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    ExitCode, InnerReceipt, MaybePruned, Receipt,
+};
+
+use crate::prove::{Guest, JournalEncoding};
+
+/// Reads a bincode encoded receipt, as stored by the service, the proof cache and `prove --out`.
+pub fn load_receipt(path: impl AsRef<Path>) -> Result<Receipt> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).with_context(|| format!("unable to read {}", path.display()))?;
+    // Cache entries start with the receipt, the stats after it are ignored.
+    bincode::deserialize(&bytes).with_context(|| format!("{} is not a receipt", path.display()))
+}
+
+/// The guests of this repository, by image ID.
+pub fn guest_name(image_id: Digest) -> Option<&'static str> {
+    [
+        (GUEST_CODE_FOR_ZK_PROOF_ID, "verifier"),
+        (
+            GUEST_CODE_FOR_ZK_PROOF_ACCELERATED_ID,
+            "verifier, accelerated sha",
+        ),
+        (GUEST_CODE_FOR_ZK_PROOF_ABI_ID, "verifier, ABI journal"),
+        (GUEST_CODE_FOR_ZK_PROOF_BATCH_ID, "batch"),
+        (GUEST_CODE_FOR_ZK_PROOF_AGGREGATE_ID, "aggregate"),
+        (GUEST_CODE_FOR_ZK_PROOF_PLONK_ID, "plonk"),
+    ]
+    .into_iter()
+    .find(|(id, _)| Digest::from(*id) == image_id)
+    .map(|(_, name)| name)
+}
+
+/// The journal, decoded according to the guest that committed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedJournal {
+    /// The verifier guests commit the digest of the request, see `VerifyZkpRequest::digest`,
+    /// and whether it is valid.
+    Valid { request_digest: [u8; 32], valid: bool },
+    /// The verifier guest with the ABI journal commits the header if it is valid.
+    Header(Option<VerifiedHeader>),
+    /// The batch guest commits the result of every header.
+    Batch(Vec<bool>),
+    /// The aggregator guest commits the image ID of the receipts it verified and their
+    /// journals: the digest of every request and its result.
+    Aggregate {
+        image_id: Digest,
+        results: Vec<([u8; 32], bool)>,
+    },
+    /// Unknown guest or undecodable journal.
+    Raw(Vec<u8>),
+}
+
+impl DecodedJournal {
+    pub fn decode(receipt: &Receipt, image_id: Digest) -> Self {
+        let raw = || DecodedJournal::Raw(receipt.journal.bytes.clone());
+        let decoded = if image_id == Digest::from(GUEST_CODE_FOR_ZK_PROOF_BATCH_ID) {
+            receipt.journal.decode().map(DecodedJournal::Batch)
+        } else if image_id == Digest::from(GUEST_CODE_FOR_ZK_PROOF_AGGREGATE_ID) {
+            receipt
+                .journal
+                .decode::<([u32; 8], Vec<([u8; 32], bool)>)>()
+                .map(|(image_id, results)| DecodedJournal::Aggregate {
+                    image_id: image_id.into(),
+                    results,
+                })
+        } else if let Some(guest) = Guest::from_image_id(image_id) {
+            return Self::decode_verifier(&receipt.journal.bytes, guest.journal_encoding())
+                .unwrap_or_else(|_| raw());
+        } else {
+            return raw();
+        };
+        decoded.unwrap_or_else(|_| raw())
+    }
+
+    /// The journal of a verifier guest, committed in `encoding`.
+    pub fn decode_verifier(journal: &[u8], encoding: JournalEncoding) -> Result<Self> {
+        match encoding {
+            JournalEncoding::Serde => {
+                let (request_digest, valid) = risc0_zkvm::serde::from_slice(journal)?;
+                Ok(DecodedJournal::Valid {
+                    request_digest,
+                    valid,
+                })
+            }
+            JournalEncoding::Abi if journal.is_empty() => Ok(DecodedJournal::Header(None)),
+            JournalEncoding::Abi => VerifiedHeader::abi_decode(journal)
+                .map(|header| DecodedJournal::Header(Some(header)))
+                .map_err(|err| anyhow::anyhow!("invalid ABI journal: {err:?}")),
+        }
+    }
+
+    /// Whether the verifier guest committed that the header is valid, `None` for the other
+    /// guests.
+    pub fn is_valid(&self) -> Option<bool> {
+        match self {
+            DecodedJournal::Valid { valid, .. } => Some(*valid),
+            DecodedJournal::Header(header) => Some(header.is_some()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DecodedJournal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodedJournal::Valid {
+                request_digest,
+                valid,
+            } => write!(f, "valid: {valid}, request digest: 0x{}", hex::encode(request_digest)),
+            DecodedJournal::Header(None) => write!(f, "valid: false"),
+            DecodedJournal::Header(Some(header)) => write!(
+                f,
+                "valid: true, chain ID: {}, height: {}, timestamp: {}, validators hash: 0x{}, \
+                 next validators hash: 0x{}, app hash: 0x{}",
+                header.chain_id,
+                header.height,
+                header.timestamp,
+                hex::encode(header.validators_hash),
+                hex::encode(header.next_validators_hash),
+                hex::encode(header.app_hash)
+            ),
+            DecodedJournal::Batch(results) => write!(f, "results: {results:?}"),
+            DecodedJournal::Aggregate { image_id, results } => {
+                write!(f, "verified image ID: {image_id}, results: [")?;
+                for (i, (request_digest, valid)) in results.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{separator}0x{}: {valid}", hex::encode(request_digest))?;
+                }
+                write!(f, "]")
+            }
+            DecodedJournal::Raw(bytes) => write!(f, "0x{}", hex::encode(bytes)),
+        }
+    }
+}
+
+/// An assumption of the receipt: the claim of another receipt it depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssumptionSummary {
+    pub claim_digest: Digest,
+    /// `None` if the assumption is pruned to its digest.
+    pub control_root: Option<Digest>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReceiptSummary {
+    pub kind: &'static str,
+    pub image_id: Digest,
+    pub claim_digest: Digest,
+    pub exit_code: ExitCode,
+    pub seal_bytes: usize,
+    /// Only composite receipts keep their segments.
+    pub segments: Option<usize>,
+    pub journal_bytes: usize,
+    pub journal: DecodedJournal,
+    /// `None` if the assumptions are pruned.
+    pub assumptions: Option<Vec<AssumptionSummary>>,
+    /// Whether the receipt verifies against its image ID.
+    pub verified: Result<(), String>,
+}
+
+impl ReceiptSummary {
+    pub fn new(receipt: &Receipt) -> Result<Self> {
+        let claim = receipt.claim().context("the receipt has no claim")?;
+        let claim_digest = claim.digest();
+        let claim = claim.as_value().context("the claim is pruned")?;
+
+        // The digest of the pre-state is the image ID.
+        let image_id = claim.pre.digest();
+        let assumptions = match &claim.output {
+            MaybePruned::Value(Some(output)) => match &output.assumptions {
+                MaybePruned::Value(assumptions) => Some(
+                    assumptions
+                        .0
+                        .iter()
+                        .map(|assumption| match assumption {
+                            MaybePruned::Value(assumption) => AssumptionSummary {
+                                claim_digest: assumption.claim,
+                                control_root: Some(assumption.control_root),
+                            },
+                            MaybePruned::Pruned(digest) => AssumptionSummary {
+                                claim_digest: *digest,
+                                control_root: None,
+                            },
+                        })
+                        .collect(),
+                ),
+                MaybePruned::Pruned(_) => None,
+            },
+            MaybePruned::Value(None) => Some(Vec::new()),
+            MaybePruned::Pruned(_) => None,
+        };
+
+        let (kind, segments) = match &receipt.inner {
+            InnerReceipt::Composite(composite) => ("composite", Some(composite.segments.len())),
+            InnerReceipt::Succinct(_) => ("succinct", None),
+            InnerReceipt::Groth16(_) => ("groth16", None),
+            InnerReceipt::Fake(_) => ("fake", None),
+            _ => ("unknown", None),
+        };
+
+        Ok(Self {
+            kind,
+            image_id,
+            claim_digest,
+            exit_code: claim.exit_code,
+            seal_bytes: receipt.seal_size(),
+            segments,
+            journal_bytes: receipt.journal.bytes.len(),
+            journal: DecodedJournal::decode(receipt, image_id),
+            assumptions,
+            verified: receipt.verify(image_id).map_err(|err| err.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ReceiptSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "kind:         {}", self.kind)?;
+        writeln!(
+            f,
+            "image ID:     {} ({})",
+            self.image_id,
+            guest_name(self.image_id).unwrap_or("unknown guest")
+        )?;
+        writeln!(f, "claim digest: {}", self.claim_digest)?;
+        writeln!(f, "exit code:    {:?}", self.exit_code)?;
+        writeln!(f, "seal size:    {} bytes", self.seal_bytes)?;
+        match self.segments {
+            Some(segments) => writeln!(f, "segments:     {segments}")?,
+            None => writeln!(f, "segments:     - (not kept in {} receipts)", self.kind)?,
+        }
+        writeln!(
+            f,
+            "journal:      {} bytes, {}",
+            self.journal_bytes, self.journal
+        )?;
+        match &self.assumptions {
+            Some(assumptions) if assumptions.is_empty() => writeln!(f, "assumptions:  none")?,
+            Some(assumptions) => {
+                writeln!(f, "assumptions:  {}", assumptions.len())?;
+                for assumption in assumptions {
+                    match assumption.control_root {
+                        Some(control_root) => writeln!(
+                            f,
+                            "  claim {}, control root {control_root}",
+                            assumption.claim_digest
+                        )?,
+                        None => writeln!(f, "  pruned {}", assumption.claim_digest)?,
+                    }
+                }
+            }
+            None => writeln!(f, "assumptions:  pruned")?,
+        }
+        match &self.verified {
+            Ok(()) => write!(f, "verified:     yes"),
+            Err(err) => write!(f, "verified:     no ({err})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_journal() {
+        let valid = DecodedJournal::Valid {
+            request_digest: [0xab; 32],
+            valid: true,
+        };
+        assert_eq!(
+            valid.to_string(),
+            format!("valid: true, request digest: 0x{}", "ab".repeat(32))
+        );
+        assert_eq!(
+            DecodedJournal::Batch(vec![true, false]).to_string(),
+            "results: [true, false]"
+        );
+        assert_eq!(DecodedJournal::Raw(vec![1, 0, 0, 0]).to_string(), "0x01000000");
+    }
+
+    #[test]
+    fn test_decode_verifier_journal() {
+        let header = VerifiedHeader {
+            chain_id: "union-devnet-1337".into(),
+            height: 969001,
+            timestamp: 1_727_858_000_123_456_789,
+            validators_hash: [1; 32],
+            next_validators_hash: [2; 32],
+            app_hash: [3; 32],
+        };
+
+        let serde: Vec<u8> = risc0_zkvm::serde::to_vec(&([7u8; 32], true))
+            .unwrap()
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        let decoded = DecodedJournal::decode_verifier(&serde, JournalEncoding::Serde).unwrap();
+        assert_eq!(
+            decoded,
+            DecodedJournal::Valid {
+                request_digest: [7; 32],
+                valid: true
+            }
+        );
+
+        let decoded =
+            DecodedJournal::decode_verifier(&header.abi_encode(), JournalEncoding::Abi).unwrap();
+        assert_eq!(decoded.is_valid(), Some(true));
+        assert_eq!(decoded, DecodedJournal::Header(Some(header)));
+
+        let decoded = DecodedJournal::decode_verifier(&[], JournalEncoding::Abi).unwrap();
+        assert_eq!(decoded.is_valid(), Some(false));
+        assert!(DecodedJournal::decode_verifier(&[1, 0, 0, 0], JournalEncoding::Abi).is_err());
+    }
+}
@@ -1,38 +1,429 @@
-use hex_literal::hex;
-use methods::{GUEST_CODE_FOR_ZK_PROOF_ELF, GUEST_CODE_FOR_ZK_PROOF_ID};
-use risc0_zkvm::{default_prover, ExecutorEnv};
-use cometbls_groth16_verifier::VerifyZkpRequest;
+use std::{fs, path::PathBuf};
 
-fn main() {
+use anyhow::{anyhow, ensure, Context, Result};
+use byteorder::BigEndian;
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+use cometbls_groth16_verifier::{codec::Codec, ZKP};
+use host::{
+    aggregate::compare_latency,
+    cache::{ProofCache, DEFAULT_MAX_BYTES},
+    compare::compare,
+    config::{self, ConfigArgs},
+    evm::{verified_header, VerifierCalldata},
+    execute::{execute_plonk_request, execute_request},
+    fixtures::{load_fixture, load_fixtures, Fixture},
+    inspect::{load_receipt, DecodedJournal, ReceiptSummary},
+    memory::track_peak_rss,
+    plonk::load_plonk_request,
+    pprof::{read_profile, render_diff, CycleTable, SortBy},
+    prove::{env_builder, prove_request_cached, prover_opts, Guest, JournalEncoding},
+    report::{group_runs, read_results, render_markdown},
+    results::{log_results, Stats},
+};
+use risc0_zkvm::default_prover;
+
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Proves a single fixture and verifies the receipt.
+    Prove {
+        /// Path to the fixture, defaults to the devnet fixture.
+        fixture: Option<PathBuf>,
+        /// Reuse the receipt cached in this directory for the same request instead of proving,
+        /// the guest is not profiled then.
+        #[arg(long)]
+        cache: Option<PathBuf>,
+        /// The least recently used receipts are evicted beyond this size.
+        #[arg(long, default_value_t = DEFAULT_MAX_BYTES, requires = "cache")]
+        cache_max_bytes: u64,
+        /// Store the receipt bincode encoded, for `inspect`.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// The guest to prove, `abi-journal` commits the header Solidity ABI encoded.
+        #[arg(long, value_enum, default_value_t = Guest::Default, conflicts_with = "cache")]
+        guest: Guest,
+    },
+    /// Prints the kind, image ID, claim, seal size, segments, journal and assumptions of a
+    /// bincode encoded receipt, as stored by `prove --out`, the service or the proof cache.
+    Inspect { receipt: PathBuf },
+    /// Prints the proof of a fixture as JSON, with hex encoded points.
+    Proof { fixture: PathBuf },
+    /// Prints the calldata verifying a Groth16 receipt of a verifier guest through the RISC
+    /// Zero verifier router, and the ABI encoded header it proves.
+    Calldata {
+        /// A bincode encoded receipt, proven with `SNARK_WRAPPING=1`.
+        receipt: PathBuf,
+        /// The fixture the receipt was proven for, to encode the verified header, rejected unless
+        /// its digest is the one the receipt commits. Not needed for receipts of the
+        /// `abi-journal` guest, which commits the header itself.
+        #[arg(long)]
+        fixture: Option<PathBuf>,
+        /// Also write the calldata as JSON.
+        #[arg(long)]
+        json: Option<PathBuf>,
+    },
+    /// Verifies every fixture natively and in the zkVM and checks both agree.
+    Compare(FixtureArgs),
+    /// Executes every fixture without proving and reports its cycle counts.
+    Execute {
+        #[command(flatten)]
+        fixtures: FixtureArgs,
+        /// The results CSV to append to, defaults to `results` in the configuration.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Compress the points of the proofs, logged as `<fixture>_compressed`. Fixtures with
+        /// invalid points are skipped.
+        #[arg(long)]
+        compressed: bool,
+    },
+    /// Executes the PLONK verifier guest on a gnark PLONK proof and reports its cycle counts, in
+    /// the same results CSV as the Groth16 fixtures.
+    ExecutePlonk {
+        /// A JSON file with the hex encoded `verifying_key`, `proof` and `public_inputs`.
+        request: PathBuf,
+        /// The results CSV to append to, defaults to `results` in the configuration.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Proves the fixtures in parallel and aggregates the receipts, then proves them again in a
+    /// single batch, and compares the latency of both.
+    Aggregate {
+        #[command(flatten)]
+        fixtures: FixtureArgs,
+        /// Headers proven concurrently.
+        #[arg(long, default_value_t = 4)]
+        workers: usize,
+        /// The comparison, overwritten.
+        #[arg(long, default_value = "aggregate.json")]
+        json: PathBuf,
+    },
+    /// Renders the results CSV as Markdown tables, one per day.
+    Report {
+        /// Defaults to `results` in the configuration.
+        #[arg(long)]
+        results: Option<PathBuf>,
+        /// Only render the run of this day (YYYY-MM-DD).
+        #[arg(long)]
+        run: Option<NaiveDate>,
+        /// Show the change of every value relative to the run of this day (YYYY-MM-DD).
+        #[arg(long)]
+        baseline: Option<NaiveDate>,
+    },
+    /// Prints the cycles per function of a profile written by the tests.
+    Profile {
+        profile: PathBuf,
+        /// Print the change of every function relative to this profile instead.
+        #[arg(long)]
+        base: Option<PathBuf>,
+        /// Only print this many functions.
+        #[arg(long)]
+        top: Option<usize>,
+        #[arg(long, value_enum, default_value_t = SortBy::Flat)]
+        sort: SortBy,
+    },
+}
+
+#[derive(Args)]
+struct FixtureArgs {
+    /// Defaults to `fixtures` in the configuration.
+    #[arg(long)]
+    fixtures: Option<PathBuf>,
+    /// Only run the fixture with this name.
+    #[arg(long)]
+    fixture: Option<String>,
+}
+
+impl FixtureArgs {
+    fn load(&self) -> Result<Vec<Fixture>> {
+        let dir = match &self.fixtures {
+            Some(dir) => dir,
+            None => &config::current()?.fixtures,
+        };
+        let fixtures: Vec<_> = load_fixtures(dir)?
+            .into_iter()
+            .filter(|fixture| self.fixture.as_ref().map_or(true, |name| &fixture.name == name))
+            .collect();
+        ensure!(!fixtures.is_empty(), "no fixtures selected");
+        Ok(fixtures)
+    }
+}
+
+fn main() -> Result<()> {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
         .init();
 
-    let input = VerifyZkpRequest {
-        chain_id: "union-devnet-1337".into(),
-        trusted_validators_hash: hex!("1B7EA0F1B3E574F8D50A12827CCEA43CFF858C2716AE05370CC40AE8EC521FD8").into(),
-            height: 3405691582,
-                seconds: 1710783278,
-                nanos: 499600406,
-            validators_hash: hex!("1B7EA0F1B3E574F8D50A12827CCEA43CFF858C2716AE05370CC40AE8EC521FD8").into(),
-            next_validators_hash: hex!("1B7EA0F1B3E574F8D50A12827CCEA43CFF858C2716AE05370CC40AE8EC521FD8").into(),
-            app_hash: hex!("3A34FC963EEFAAE9B7C0D3DFF89180D91F3E31073E654F732340CEEDD77DD25B").into(),
-        zkp: hex!("294A48A750D5C2CF926516752FF484EEBE55FF26CF8A8A7536D98794CF062DB6214D0C9E5C6B164111927A1630889619DBBB40149D8E2D32898E7ACB765542CD0EB8A8E04CCC254C3BFDC2FCE627D59C3C05E2AC76E03977855DD889C1C9BA432FF7FF4DEFCB5286555D36D22DD073A859140508AF9B977F38EB9A604E99A5F6109D43A4AFA0AB161DA2B261DED80FBC0C36E57DE2001338941C834E3262CF751BC1BFC6EC27BB8E106BAAB976285BAC1D4AC38D1B759C8A2852D65CE239974F1275CC6765B3D174FD1122EFDE86137D19F07483FEF5244B1D74B2D9DC598AC32A5CA10E8837FBC89703F4D0D46912CF4AF82341C30C2A1F3941849CC011A56E18AD2162EEB71289B8821CC01875BC1E35E5FC1EBD9114C0B2C0F0D9A96C394001468C70A1716CA98EBE82B1E614D4D9B07292EBAD5B60E0C76FD1D58B485E7D1FB1E07F51A0C68E4CA59A399FCF0634D9585BE478E37480423681B984E96C0A1698D8FCB1DF51CAE023B045E114EED9CB233A5742D9E60E1097206EB20A5058").into(),
+    let cli = Cli::parse();
+    let config = cli.config.install()?;
+
+    match cli.command {
+        Command::Prove {
+            fixture,
+            cache,
+            cache_max_bytes,
+            out,
+            guest,
+        } => prove(fixture, cache, cache_max_bytes, out, guest),
+        Command::Inspect { receipt } => {
+            println!("{}", ReceiptSummary::new(&load_receipt(receipt)?)?);
+            Ok(())
+        }
+        Command::Proof { fixture } => print_proof(fixture),
+        Command::Calldata {
+            receipt,
+            fixture,
+            json,
+        } => calldata(receipt, fixture, json),
+        Command::Compare(fixtures) => compare_fixtures(fixtures),
+        Command::Execute {
+            fixtures,
+            out,
+            compressed,
+        } => execute_fixtures(
+            fixtures,
+            out.unwrap_or_else(|| config.results.clone()),
+            compressed,
+        ),
+        Command::ExecutePlonk { request, out } => {
+            execute_plonk(request, out.unwrap_or_else(|| config.results.clone()))
+        }
+        Command::Aggregate {
+            fixtures,
+            workers,
+            json,
+        } => aggregate_fixtures(fixtures, workers, json),
+        Command::Report {
+            results,
+            run,
+            baseline,
+        } => report(results.unwrap_or_else(|| config.results.clone()), run, baseline),
+        Command::Profile {
+            profile,
+            base,
+            top,
+            sort,
+        } => {
+            let table = CycleTable::new(&read_profile(profile)?)?;
+            match base {
+                Some(base) => {
+                    let base = CycleTable::new(&read_profile(base)?)?;
+                    print!("{}", render_diff(&base, &table, sort, top));
+                }
+                None => print!("{}", table.render_markdown(sort, top)),
+            }
+            Ok(())
+        }
+    }
+}
+
+fn prove(
+    fixture: Option<PathBuf>,
+    cache: Option<PathBuf>,
+    cache_max_bytes: u64,
+    out: Option<PathBuf>,
+    guest: Guest,
+) -> Result<()> {
+    let config = config::current()?;
+    let path = fixture.unwrap_or_else(|| config.fixtures.join("valid_proof.json"));
+    let input = load_fixture(path)?.request();
+
+    let receipt = match cache {
+        Some(dir) => {
+            let cache = ProofCache::open(dir, cache_max_bytes)?;
+            let (receipt, _, cached) = prove_request_cached(&cache, &input, &prover_opts()?)?;
+            if cached {
+                println!("using cached receipt");
+            }
+            receipt
+        }
+        None => {
+            fs::create_dir_all(&config.profile_dir)?;
+            let env = env_builder()?
+                .enable_profiler(config.profile_dir.join("host.pb"))
+                .write(&input)?
+                .build()?;
+
+            let prover = default_prover();
+            prover.prove_with_opts(env, guest.elf(), &prover_opts()?)?.receipt
+        }
     };
+    let journal =
+        DecodedJournal::decode_verifier(&receipt.journal.bytes, guest.journal_encoding())?;
+    println!("{journal}");
+    guest.committed_validity(&receipt.journal.bytes, &input)?;
+    receipt.verify(guest.image_id())?;
+    if let Some(out) = out {
+        fs::write(&out, bincode::serialize(&receipt)?)
+            .with_context(|| format!("unable to write {}", out.display()))?;
+    }
+
+    Ok(())
+}
+
+fn calldata(receipt: PathBuf, fixture: Option<PathBuf>, json: Option<PathBuf>) -> Result<()> {
+    let receipt = load_receipt(receipt)?;
+    let summary = ReceiptSummary::new(&receipt)?;
+    let guest = Guest::from_image_id(summary.image_id)
+        .context("not a receipt of the verifier guests")?;
+    receipt.verify(guest.image_id())?;
+
+    let calldata = VerifierCalldata::new(&receipt, guest.image_id())?;
+    println!("seal: 0x{}", hex::encode(&calldata.seal));
+    println!("image ID: 0x{}", hex::encode(calldata.image_id));
+    println!("journal digest: 0x{}", hex::encode(calldata.journal_digest));
+    println!("calldata: 0x{}", hex::encode(&calldata.calldata));
+    if fixture.is_some() || guest.journal_encoding() == JournalEncoding::Abi {
+        let request = fixture.map(load_fixture).transpose()?.map(|fixture| fixture.request());
+        let header = verified_header(&receipt, guest, request.as_ref())?;
+        println!("header: 0x{}", hex::encode(header));
+    }
+    if let Some(json) = json {
+        fs::write(json, serde_json::to_vec_pretty(&calldata)?)?;
+    }
+
+    Ok(())
+}
+
+fn compare_fixtures(args: FixtureArgs) -> Result<()> {
+    let mut mismatches = 0;
+
+    println!("| fixture                    | expected | native               | zkvm  |");
+    println!("|----------------------------|----------|----------------------|-------|");
+    for fixture in args.load()? {
+        let comparison = compare(&fixture)?;
+        println!(
+            "| {:<26} | {:<8} | {:<20} | {:<5} |{}",
+            comparison.name,
+            comparison.expected,
+            format!("{:?}", comparison.native),
+            comparison.zkvm,
+            if comparison.is_identical() { "" } else { " MISMATCH" }
+        );
+        if !comparison.is_identical() {
+            mismatches += 1;
+        }
+    }
 
-    let env = ExecutorEnv::builder()
-        .enable_profiler("./host.pb")
-        .write(&input)
-        .unwrap()
-        .build()
-        .unwrap();
+    ensure!(mismatches == 0, "{mismatches} fixture(s) differ between native and zkVM execution");
 
-    let prover = default_prover();
-    let prove_info = prover.prove(env, GUEST_CODE_FOR_ZK_PROOF_ELF).unwrap();
-    let receipt = prove_info.receipt;
-    let result: bool = receipt.journal.decode().unwrap();
-    println!("result: {result}");
-    receipt.verify(GUEST_CODE_FOR_ZK_PROOF_ID).unwrap();
+    Ok(())
+}
+
+fn execute_fixtures(args: FixtureArgs, out: PathBuf, compressed: bool) -> Result<()> {
+    for fixture in args.load()? {
+        let (name, request) = if compressed {
+            let Some(request) = fixture.compressed_request() else {
+                println!("{}: skipped, the proof has invalid points", fixture.name);
+                continue;
+            };
+            (format!("{}_compressed", fixture.name), request)
+        } else {
+            (fixture.name.clone(), fixture.request())
+        };
+
+        let (result, peak_rss_bytes) = track_peak_rss(|| execute_request(&request));
+        let (session, duration) = result?;
+        let stats = Stats::from(&session);
+
+        let po2s: Vec<_> = session.segments.iter().map(|segment| segment.po2).collect();
+        println!(
+            "{}: {} segments, {} total cycles, {} user cycles, po2 {:?}",
+            name, stats.segments, stats.total_cycles, stats.user_cycles, po2s
+        );
+        log_results(&out, &name, &stats, duration, peak_rss_bytes)?;
+    }
+
+    Ok(())
+}
+
+fn print_proof(path: PathBuf) -> Result<()> {
+    let fixture = load_fixture(&path)?;
+    let zkp = ZKP::<BigEndian>::decode(&fixture.request.zkp)
+        .map_err(|err| anyhow!("{}: invalid proof: {err:?}", fixture.name))?;
+    println!("{}", serde_json::to_string_pretty(&zkp)?);
+    Ok(())
+}
+
+fn execute_plonk(path: PathBuf, out: PathBuf) -> Result<()> {
+    let request = load_plonk_request(&path)?;
+    let (result, peak_rss_bytes) = track_peak_rss(|| execute_plonk_request(&request));
+    let (session, duration, valid) = result?;
+    let stats = Stats::from(&session);
+
+    let name = format!(
+        "plonk_{}",
+        path.file_stem().context("request without a file name")?.to_string_lossy()
+    );
+    println!(
+        "{}: {}, {} segments, {} total cycles, {} user cycles",
+        name,
+        if valid { "valid" } else { "invalid" },
+        stats.segments,
+        stats.total_cycles,
+        stats.user_cycles
+    );
+    log_results(&out, &name, &stats, duration, peak_rss_bytes)?;
+
+    Ok(())
+}
+
+fn aggregate_fixtures(args: FixtureArgs, workers: usize, json: PathBuf) -> Result<()> {
+    let requests: Vec<_> = args.load()?.iter().map(Fixture::request).collect();
+    let comparison = compare_latency(&requests, workers, &prover_opts()?)?;
+
+    println!("| approach                   | headers | workers |   millis | total_cycles |");
+    println!("|----------------------------|--------:|--------:|---------:|-------------:|");
+    println!(
+        "| {:<26} | {:>7} | {:>7} | {:>8} | {:>12} |",
+        "parallel + aggregate",
+        comparison.headers,
+        comparison.workers,
+        comparison.aggregated_millis,
+        comparison.aggregated_total_cycles
+    );
+    println!(
+        "| {:<26} | {:>7} | {:>7} | {:>8} | {:>12} |",
+        "batch", comparison.headers, 1, comparison.batch_millis, comparison.batch_total_cycles
+    );
+    println!(
+        "proving the headers took {} ms, aggregating them {} ms",
+        comparison.headers_millis, comparison.aggregate_millis
+    );
+
+    std::fs::write(json, serde_json::to_vec_pretty(&comparison)?)?;
+
+    Ok(())
+}
+
+fn report(results: PathBuf, run: Option<NaiveDate>, baseline: Option<NaiveDate>) -> Result<()> {
+    let runs = group_runs(&read_results(results)?);
+
+    let baseline = baseline
+        .map(|date| {
+            runs.iter()
+                .find(|run| run.date == date)
+                .with_context(|| format!("no results for baseline {date}"))
+        })
+        .transpose()?;
+    let selected: Vec<_> = runs
+        .iter()
+        .filter(|r| run.map_or(true, |date| r.date == date))
+        .filter(|r| baseline.map_or(true, |baseline| r.date != baseline.date))
+        .cloned()
+        .collect();
+    ensure!(!selected.is_empty(), "no runs selected");
+
+    print!("{}", render_markdown(&selected, baseline));
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -40,4 +431,4 @@ mod tests {
 
     #[test]
     fn test_ok() {}
-}
\ No newline at end of file
+}
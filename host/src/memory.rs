@@ -0,0 +1,33 @@
+//! Peak resident memory of this process, read from `/proc` and therefore only available on Linux.
+//!
+//! The local prover runs in-process, so this includes the memory used for proving.
+
+use std::fs;
+
+/// The peak resident set size (`VmHWM`) since the start of the process or the last
+/// [`reset_peak_rss`].
+pub fn peak_rss_bytes() -> Option<u64> {
+    read_kb_field("/proc/self/status", "VmHWM:")
+}
+
+/// Resets the peak resident set size to the current one, returns whether that is supported.
+pub fn reset_peak_rss() -> bool {
+    fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// Reads a `<field> <n> kB` line as found in `/proc/meminfo` and `/proc/<pid>/status`, in bytes.
+pub(crate) fn read_kb_field(path: &str, field: &str) -> Option<u64> {
+    fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix(field))
+        .and_then(|kb| kb.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+/// Runs `f`, returning the peak resident memory while it ran, `None` if that cannot be tracked.
+pub fn track_peak_rss<T>(f: impl FnOnce() -> T) -> (T, Option<u64>) {
+    let tracked = reset_peak_rss();
+    let value = f();
+    (value, peak_rss_bytes().filter(|_| tracked))
+}
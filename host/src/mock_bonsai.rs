@@ -0,0 +1,467 @@
+//! A local stand-in for Bonsai, implementing the endpoints `BonsaiProver` uses on top of the
+//! local prover, so the remote proving path can be tested without network access.
+//!
+//! Uploads are kept in memory and sessions are proven one at a time, in the order they were
+//! created. Receipts are succinct, like the ones Bonsai returns; SNARK requests wrap them into
+//! Groth16. In dev mode, all receipts are fake.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Condvar, Mutex,
+    },
+    time::Instant,
+};
+
+use anyhow::{bail, Context, Result};
+use risc0_zkvm::{
+    get_prover_server, AssumptionReceipt, ExecutorEnv, InnerReceipt, ProverOpts, Receipt, VERSION,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::http::{path_segments, Response};
+
+/// The request body of `POST /sessions/create`.
+#[derive(Debug, Deserialize)]
+struct ProofReq {
+    img: String,
+    input: String,
+    #[serde(default)]
+    assumptions: Vec<String>,
+    #[serde(default)]
+    execute_only: bool,
+}
+
+/// The request body of `POST /snark/create`.
+#[derive(Debug, Deserialize)]
+struct SnarkReq {
+    session_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Upload {
+    Image,
+    Input,
+    Receipt,
+}
+
+impl Upload {
+    fn from_route(route: &str) -> Option<Self> {
+        match route {
+            "images" => Some(Upload::Image),
+            "inputs" => Some(Upload::Input),
+            "receipts" => Some(Upload::Receipt),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Status {
+    Running,
+    Succeeded {
+        receipt: Vec<u8>,
+        /// `(segments, total_cycles, user_cycles)`, not known for SNARKs.
+        stats: Option<(usize, u64, u64)>,
+    },
+    Failed(String),
+}
+
+#[derive(Debug)]
+struct Task {
+    started: Instant,
+    status: Status,
+}
+
+#[derive(Debug)]
+enum Work {
+    Session { id: String, request: ProofReq },
+    Snark { id: String, session: String },
+}
+
+pub struct MockBonsai {
+    /// The URL the server is reachable at, for the upload and download URLs handed out.
+    url: String,
+    uploads: Mutex<HashMap<(Upload, String), Vec<u8>>>,
+    sessions: Mutex<HashMap<String, Task>>,
+    snarks: Mutex<HashMap<String, Task>>,
+    queue: Mutex<VecDeque<Work>>,
+    queued: Condvar,
+    next_id: AtomicU64,
+}
+
+impl MockBonsai {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_owned(),
+            uploads: Mutex::default(),
+            sessions: Mutex::default(),
+            snarks: Mutex::default(),
+            queue: Mutex::default(),
+            queued: Condvar::new(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn new_id(&self) -> String {
+        format!("{:032x}", self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {
+        match (method, path_segments(path).as_slice()) {
+            ("GET", ["images", "upload", image_id]) => {
+                if self.has_upload(Upload::Image, image_id) {
+                    Response::empty(204)
+                } else {
+                    Response::json(
+                        200,
+                        &json!({ "url": format!("{}/upload/images/{image_id}", self.url) }),
+                    )
+                }
+            }
+            ("GET", [route @ ("inputs" | "receipts"), "upload"]) => {
+                let id = self.new_id();
+                Response::json(
+                    200,
+                    &json!({ "url": format!("{}/upload/{route}/{id}", self.url), "uuid": id }),
+                )
+            }
+            ("PUT", ["upload", route, id]) => match Upload::from_route(route) {
+                Some(upload) => {
+                    self.uploads
+                        .lock()
+                        .unwrap()
+                        .insert((upload, id.to_string()), body.to_vec());
+                    Response::empty(200)
+                }
+                None => Response::error(404, format!("not found: {path}")),
+            },
+            ("DELETE", [route @ ("images" | "inputs"), id]) => {
+                let upload = Upload::from_route(route).expect("matched above; qed;");
+                match self
+                    .uploads
+                    .lock()
+                    .unwrap()
+                    .remove(&(upload, id.to_string()))
+                {
+                    Some(_) => Response::empty(204),
+                    None => Response::error(404, format!("unknown {route} {id}")),
+                }
+            }
+            ("POST", ["sessions", "create"]) => match serde_json::from_slice::<ProofReq>(body) {
+                Ok(request) => self.create_session(request),
+                Err(err) => Response::error(400, format!("invalid request: {err}")),
+            },
+            ("GET", ["sessions", "status", id]) => match self.sessions.lock().unwrap().get(*id) {
+                Some(task) => Response::json(200, &self.session_status(id, task)),
+                None => Response::error(404, format!("unknown session {id}")),
+            },
+            ("GET", ["sessions", "logs", id]) => match self.sessions.lock().unwrap().get(*id) {
+                Some(_) => Response::empty(200),
+                None => Response::error(404, format!("unknown session {id}")),
+            },
+            ("GET", ["receipts", id]) => match self.sessions.lock().unwrap().get(*id) {
+                Some(Task {
+                    status: Status::Succeeded { .. },
+                    ..
+                }) => Response::json(
+                    200,
+                    &json!({ "url": format!("{}/download/sessions/{id}", self.url) }),
+                ),
+                _ => Response::error(404, format!("no receipt for session {id}")),
+            },
+            ("GET", ["download", kind @ ("sessions" | "snarks"), id]) => {
+                let tasks = if *kind == "sessions" {
+                    self.sessions.lock().unwrap()
+                } else {
+                    self.snarks.lock().unwrap()
+                };
+                match tasks.get(*id) {
+                    Some(Task {
+                        status: Status::Succeeded { receipt, .. },
+                        ..
+                    }) => Response::bytes(receipt.clone()),
+                    _ => Response::error(404, format!("no receipt for {id}")),
+                }
+            }
+            ("POST", ["snark", "create"]) => match serde_json::from_slice::<SnarkReq>(body) {
+                Ok(request) => self.create_snark(request),
+                Err(err) => Response::error(400, format!("invalid request: {err}")),
+            },
+            ("GET", ["snark", "status", id]) => match self.snarks.lock().unwrap().get(*id) {
+                Some(task) => Response::json(200, &self.snark_status(id, task)),
+                None => Response::error(404, format!("unknown snark {id}")),
+            },
+            ("GET", ["version"]) => Response::json(200, &json!({ "risc0_zkvm": [VERSION] })),
+            ("GET", ["user", "quotas"]) => Response::json(
+                200,
+                &json!({
+                    "exec_cycle_limit": i64::MAX,
+                    "concurrent_proofs": 1,
+                    "cycle_budget": i64::MAX,
+                    "cycle_usage": 0,
+                    "dedicated_executor": 0,
+                    "dedicated_gpu": 0,
+                }),
+            ),
+            _ => Response::error(404, format!("not found: {method} {path}")),
+        }
+    }
+
+    fn has_upload(&self, upload: Upload, id: &str) -> bool {
+        self.uploads
+            .lock()
+            .unwrap()
+            .contains_key(&(upload, id.to_owned()))
+    }
+
+    fn create_session(&self, request: ProofReq) -> Response {
+        if request.execute_only {
+            return Response::error(400, "execute only sessions are not supported");
+        }
+        if !self.has_upload(Upload::Image, &request.img) {
+            return Response::error(404, format!("unknown image {}", request.img));
+        }
+        if !self.has_upload(Upload::Input, &request.input) {
+            return Response::error(404, format!("unknown input {}", request.input));
+        }
+        if let Some(receipt) = request
+            .assumptions
+            .iter()
+            .find(|receipt| !self.has_upload(Upload::Receipt, receipt))
+        {
+            return Response::error(404, format!("unknown receipt {receipt}"));
+        }
+
+        let id = self.new_id();
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(id.clone(), Task::new());
+        self.enqueue(Work::Session {
+            id: id.clone(),
+            request,
+        });
+        Response::json(200, &json!({ "uuid": id }))
+    }
+
+    fn create_snark(&self, request: SnarkReq) -> Response {
+        match self.sessions.lock().unwrap().get(&request.session_id) {
+            Some(Task {
+                status: Status::Succeeded { .. },
+                ..
+            }) => {}
+            Some(_) => {
+                return Response::error(
+                    400,
+                    format!("session {} has not succeeded", request.session_id),
+                )
+            }
+            None => return Response::error(404, format!("unknown session {}", request.session_id)),
+        }
+
+        let id = self.new_id();
+        self.snarks.lock().unwrap().insert(id.clone(), Task::new());
+        self.enqueue(Work::Snark {
+            id: id.clone(),
+            session: request.session_id,
+        });
+        Response::json(200, &json!({ "uuid": id }))
+    }
+
+    fn session_status(&self, id: &str, task: &Task) -> serde_json::Value {
+        let (status, receipt_url, error_msg, stats) = match &task.status {
+            Status::Running => ("RUNNING", None, None, None),
+            Status::Succeeded { stats, .. } => (
+                "SUCCEEDED",
+                Some(format!("{}/download/sessions/{id}", self.url)),
+                None,
+                stats.map(|(segments, total_cycles, cycles)| {
+                    json!({ "segments": segments, "total_cycles": total_cycles, "cycles": cycles })
+                }),
+            ),
+            Status::Failed(error) => ("FAILED", None, Some(error.clone()), None),
+        };
+        json!({
+            "status": status,
+            "receipt_url": receipt_url,
+            "error_msg": error_msg,
+            "state": matches!(task.status, Status::Running).then_some("InProgress"),
+            "elapsed_time": task.started.elapsed().as_secs_f64(),
+            "stats": stats,
+        })
+    }
+
+    fn snark_status(&self, id: &str, task: &Task) -> serde_json::Value {
+        let (status, output, error_msg) = match &task.status {
+            Status::Running => ("RUNNING", None, None),
+            Status::Succeeded { .. } => (
+                "SUCCEEDED",
+                Some(format!("{}/download/snarks/{id}", self.url)),
+                None,
+            ),
+            Status::Failed(error) => ("FAILED", None, Some(error.clone())),
+        };
+        json!({ "status": status, "output": output, "error_msg": error_msg })
+    }
+
+    fn enqueue(&self, work: Work) {
+        self.queue.lock().unwrap().push_back(work);
+        self.queued.notify_one();
+    }
+
+    /// Proves the created sessions and SNARKs forever, blocking while there are none.
+    pub fn work(&self) {
+        loop {
+            let work = {
+                let mut queue = self.queue.lock().unwrap();
+                loop {
+                    match queue.pop_front() {
+                        Some(work) => break work,
+                        None => queue = self.queued.wait(queue).unwrap(),
+                    }
+                }
+            };
+
+            let (tasks, id, result) = match work {
+                Work::Session { id, request } => (&self.sessions, id, self.prove_session(&request)),
+                Work::Snark { id, session } => (&self.snarks, id, self.prove_snark(&session)),
+            };
+            let status = result.unwrap_or_else(|err| Status::Failed(format!("{err:#}")));
+            if let Some(task) = tasks.lock().unwrap().get_mut(&id) {
+                task.status = status;
+            }
+        }
+    }
+
+    fn upload(&self, upload: Upload, id: &str) -> Result<Vec<u8>> {
+        self.uploads
+            .lock()
+            .unwrap()
+            .get(&(upload, id.to_owned()))
+            .cloned()
+            .with_context(|| format!("{upload:?} {id} was deleted"))
+    }
+
+    fn prove_session(&self, request: &ProofReq) -> Result<Status> {
+        let elf = self.upload(Upload::Image, &request.img)?;
+        let input = self.upload(Upload::Input, &request.input)?;
+
+        let mut env = ExecutorEnv::builder();
+        env.write_slice(&input);
+        for id in &request.assumptions {
+            let receipt: InnerReceipt = bincode::deserialize(&self.upload(Upload::Receipt, id)?)?;
+            env.add_assumption(AssumptionReceipt::Proven(receipt));
+        }
+
+        let opts = ProverOpts::succinct();
+        let prove_info = get_prover_server(&opts)?.prove(env.build()?, &elf)?;
+        Ok(Status::Succeeded {
+            receipt: bincode::serialize(&prove_info.receipt)?,
+            stats: Some((
+                prove_info.stats.segments,
+                prove_info.stats.total_cycles,
+                prove_info.stats.user_cycles,
+            )),
+        })
+    }
+
+    fn prove_snark(&self, session: &str) -> Result<Status> {
+        let receipt = match self.sessions.lock().unwrap().get(session) {
+            Some(Task {
+                status: Status::Succeeded { receipt, .. },
+                ..
+            }) => receipt.clone(),
+            _ => bail!("session {session} has no receipt"),
+        };
+        let receipt: Receipt = bincode::deserialize(&receipt)?;
+
+        let opts = ProverOpts::groth16();
+        let receipt = get_prover_server(&opts)?.compress(&opts, &receipt)?;
+        Ok(Status::Succeeded {
+            receipt: bincode::serialize(&receipt)?,
+            stats: None,
+        })
+    }
+}
+
+impl Task {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            status: Status::Running,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uploads() {
+        let bonsai = MockBonsai::new("http://127.0.0.1:8081/");
+
+        let response = bonsai.handle("GET", "/images/upload/abc", &[]);
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&response.body).unwrap()["url"],
+            "http://127.0.0.1:8081/upload/images/abc"
+        );
+        assert_eq!(
+            bonsai.handle("PUT", "/upload/images/abc", b"elf").status,
+            200
+        );
+        assert_eq!(bonsai.handle("GET", "/images/upload/abc", &[]).status, 204);
+
+        let response = bonsai.handle("GET", "/inputs/upload", &[]);
+        let input: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        let input_id = input["uuid"].as_str().unwrap();
+        assert_eq!(
+            input["url"],
+            format!("http://127.0.0.1:8081/upload/inputs/{input_id}")
+        );
+        assert_eq!(
+            bonsai
+                .handle("PUT", &format!("/upload/inputs/{input_id}"), b"input")
+                .status,
+            200
+        );
+
+        let create = |img: &str| {
+            bonsai.handle(
+                "POST",
+                "/sessions/create",
+                json!({ "img": img, "input": input_id, "assumptions": [], "execute_only": false })
+                    .to_string()
+                    .as_bytes(),
+            )
+        };
+        assert_eq!(create("unknown").status, 404);
+        let response = create("abc");
+        assert_eq!(response.status, 200);
+        let session: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+
+        let response = bonsai.handle(
+            "GET",
+            &format!("/sessions/status/{}", session["uuid"].as_str().unwrap()),
+            &[],
+        );
+        let status: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(status["status"], "RUNNING");
+        assert_eq!(
+            bonsai
+                .handle(
+                    "POST",
+                    "/snark/create",
+                    json!({ "session_id": session["uuid"] })
+                        .to_string()
+                        .as_bytes()
+                )
+                .status,
+            400
+        );
+    }
+}
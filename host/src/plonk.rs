@@ -0,0 +1,41 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use cometbls_groth16_verifier::plonk::VerifyPlonkRequest;
+use serde::{Deserialize, Serialize};
+
+/// A gnark PLONK proof to compare against the Groth16 fixtures, [`VerifyPlonkRequest`] with hex
+/// encoded fields.
+///
+/// `proof` is gnark's raw `WriteRawTo` encoding, `verifying_key` the fields of
+/// `PlonkVerifyingKey` in gnark's raw encoding, as `fixtures/gnark` writes them, and
+/// `public_inputs` the big endian scalars of the public witness.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlonkRequestFile {
+    #[serde(with = "hex")]
+    pub verifying_key: Vec<u8>,
+    #[serde(with = "hex")]
+    pub proof: Vec<u8>,
+    pub public_inputs: Vec<PublicInput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct PublicInput(#[serde(with = "hex")] pub [u8; 32]);
+
+impl From<PlonkRequestFile> for VerifyPlonkRequest {
+    fn from(file: PlonkRequestFile) -> Self {
+        VerifyPlonkRequest {
+            verifying_key: file.verifying_key,
+            proof: file.proof,
+            public_inputs: file.public_inputs.into_iter().map(|input| input.0).collect(),
+        }
+    }
+}
+
+pub fn load_plonk_request(path: &Path) -> Result<VerifyPlonkRequest> {
+    let json = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let file: PlonkRequestFile =
+        serde_json::from_slice(&json).with_context(|| format!("parsing {}", path.display()))?;
+    Ok(file.into())
+}
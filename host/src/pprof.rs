@@ -0,0 +1,357 @@
+//! Analysis of the pprof profiles written by `ExecutorEnvBuilder::enable_profiler`.
+//!
+//! Only the parts of the [pprof format](https://github.com/google/pprof/blob/main/proto/profile.proto)
+//! needed to attribute cycles to functions are decoded.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Profile {
+    #[prost(message, repeated, tag = "2")]
+    pub sample: Vec<Sample>,
+    #[prost(message, repeated, tag = "4")]
+    pub location: Vec<Location>,
+    #[prost(message, repeated, tag = "5")]
+    pub function: Vec<Function>,
+    #[prost(string, repeated, tag = "6")]
+    pub string_table: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Sample {
+    /// The leaf is at `location_id[0]`.
+    #[prost(uint64, repeated, tag = "1")]
+    pub location_id: Vec<u64>,
+    #[prost(int64, repeated, tag = "2")]
+    pub value: Vec<i64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Location {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    /// Inlined functions first, the caller last.
+    #[prost(message, repeated, tag = "4")]
+    pub line: Vec<Line>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Line {
+    #[prost(uint64, tag = "1")]
+    pub function_id: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Function {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    /// Index into the string table.
+    #[prost(int64, tag = "2")]
+    pub name: i64,
+}
+
+/// Cycles attributed to a function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FunctionCycles {
+    /// Cycles spent in the function itself.
+    pub flat: u64,
+    /// Cycles spent in the function and everything it calls.
+    pub cum: u64,
+}
+
+/// Per function cycle counts of a profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CycleTable {
+    pub total: u64,
+    pub functions: BTreeMap<String, FunctionCycles>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortBy {
+    Flat,
+    Cum,
+}
+
+pub fn read_profile(path: impl AsRef<Path>) -> Result<Profile> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).with_context(|| format!("unable to read {}", path.display()))?;
+    Profile::decode(bytes.as_slice()).with_context(|| format!("unable to decode {}", path.display()))
+}
+
+impl CycleTable {
+    /// Every function is counted at most once per sample towards `cum`, so recursion does not
+    /// inflate it.
+    pub fn new(profile: &Profile) -> Result<Self> {
+        let string = |index: i64| {
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| profile.string_table.get(index))
+                .with_context(|| format!("invalid string index {index}"))
+        };
+        let functions = profile
+            .function
+            .iter()
+            .map(|function| Ok((function.id, string(function.name)?.as_str())))
+            .collect::<Result<HashMap<_, _>>>()?;
+        let locations: HashMap<_, _> = profile
+            .location
+            .iter()
+            .map(|location| (location.id, location))
+            .collect();
+
+        let mut table = CycleTable::default();
+        for sample in &profile.sample {
+            let cycles = sample.value.first().copied().unwrap_or_default().max(0) as u64;
+            table.total += cycles;
+
+            let mut stack = Vec::new();
+            for id in &sample.location_id {
+                let location = locations
+                    .get(id)
+                    .with_context(|| format!("unknown location {id}"))?;
+                for line in &location.line {
+                    stack.push(
+                        *functions
+                            .get(&line.function_id)
+                            .with_context(|| format!("unknown function {}", line.function_id))?,
+                    );
+                }
+            }
+
+            if let Some(leaf) = stack.first() {
+                table.functions.entry((*leaf).to_owned()).or_default().flat += cycles;
+            }
+            for name in stack.into_iter().collect::<BTreeSet<_>>() {
+                table.functions.entry(name.to_owned()).or_default().cum += cycles;
+            }
+        }
+
+        Ok(table)
+    }
+
+    /// The functions ordered by `sort_by` descending, ties broken by name.
+    pub fn sorted(&self, sort_by: SortBy) -> Vec<(&str, FunctionCycles)> {
+        let mut functions: Vec<_> = self
+            .functions
+            .iter()
+            .map(|(name, cycles)| (name.as_str(), *cycles))
+            .collect();
+        functions.sort_by(|(a_name, a), (b_name, b)| {
+            let key = |cycles: &FunctionCycles| match sort_by {
+                SortBy::Flat => (cycles.flat, cycles.cum),
+                SortBy::Cum => (cycles.cum, cycles.flat),
+            };
+            key(b).cmp(&key(a)).then(a_name.cmp(b_name))
+        });
+        functions
+    }
+
+    /// Renders the `top` functions in the format of `pprof -top`, as a Markdown table.
+    pub fn render_markdown(&self, sort_by: SortBy, top: Option<usize>) -> String {
+        let rows = self.sorted(sort_by);
+        let rows = &rows[..top.unwrap_or(rows.len()).min(rows.len())];
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max(4);
+        let percent = |cycles: u64| percentage(cycles, self.total);
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "| {:>9} | {:>7} | {:>6} | {:>9} | {:>7} | {:<width$} |",
+            "Flat", "Flat%", "Sum%", "Cum", "Cum%", "Name"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "|----------:|--------:|-------:|----------:|--------:|{}|",
+            "-".repeat(width + 2)
+        )
+        .unwrap();
+        let mut sum = 0;
+        for (name, cycles) in rows {
+            sum += cycles.flat;
+            writeln!(
+                out,
+                "| {:>9} | {:>7} | {:>6} | {:>9} | {:>7} | {:<width$} |",
+                cycles.flat,
+                percent(cycles.flat),
+                percent(sum),
+                cycles.cum,
+                percent(cycles.cum),
+                name
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+/// Renders the change of every function from `base` to `new`, ordered by the absolute change.
+pub fn render_diff(base: &CycleTable, new: &CycleTable, sort_by: SortBy, top: Option<usize>) -> String {
+    let names: BTreeSet<_> = base.functions.keys().chain(new.functions.keys()).collect();
+    let mut rows: Vec<_> = names
+        .into_iter()
+        .map(|name| {
+            let base = base.functions.get(name).copied().unwrap_or_default();
+            let new = new.functions.get(name).copied().unwrap_or_default();
+            (name, base, new)
+        })
+        .filter(|(_, base, new)| base != new)
+        .collect();
+    let change = |base: &FunctionCycles, new: &FunctionCycles| match sort_by {
+        SortBy::Flat => new.flat as i128 - base.flat as i128,
+        SortBy::Cum => new.cum as i128 - base.cum as i128,
+    };
+    rows.sort_by(|(a_name, a_base, a_new), (b_name, b_base, b_new)| {
+        change(b_base, b_new)
+            .abs()
+            .cmp(&change(a_base, a_new).abs())
+            .then(a_name.cmp(b_name))
+    });
+    let rows = &rows[..top.unwrap_or(rows.len()).min(rows.len())];
+    let width = rows.iter().map(|(name, ..)| name.len()).max().unwrap_or(0).max(4);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "Total: {} -> {} ({:+})\n",
+        base.total,
+        new.total,
+        new.total as i128 - base.total as i128
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "| {:>9} | {:>9} | {:>10} | {:>9} | {:>9} | {:>10} | {:<width$} |",
+        "Flat", "Flat new", "ΔFlat", "Cum", "Cum new", "ΔCum", "Name"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "|----------:|----------:|-----------:|----------:|----------:|-----------:|{}|",
+        "-".repeat(width + 2)
+    )
+    .unwrap();
+    for (name, base, new) in rows {
+        writeln!(
+            out,
+            "| {:>9} | {:>9} | {:>+10} | {:>9} | {:>9} | {:>+10} | {:<width$} |",
+            base.flat,
+            new.flat,
+            new.flat as i128 - base.flat as i128,
+            base.cum,
+            new.cum,
+            new.cum as i128 - base.cum as i128,
+            name
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn percentage(cycles: u64, total: u64) -> String {
+    if total == 0 {
+        return "0.00%".to_owned();
+    }
+    format!("{:.2}%", cycles as f64 / total as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> Profile {
+        Profile {
+            sample: vec![
+                // main -> a -> b
+                Sample {
+                    location_id: vec![3, 2, 1],
+                    value: vec![10],
+                },
+                // main -> a
+                Sample {
+                    location_id: vec![2, 1],
+                    value: vec![5],
+                },
+                // main -> a -> a (recursion)
+                Sample {
+                    location_id: vec![2, 2, 1],
+                    value: vec![1],
+                },
+            ],
+            location: (1..=3)
+                .map(|id| Location {
+                    id,
+                    line: vec![Line { function_id: id }],
+                })
+                .collect(),
+            function: (1..=3)
+                .map(|id| Function {
+                    id,
+                    name: id as i64,
+                })
+                .collect(),
+            string_table: vec!["".into(), "main".into(), "a".into(), "b".into()],
+        }
+    }
+
+    #[test]
+    fn test_cycle_table() {
+        let table = CycleTable::new(&profile()).unwrap();
+
+        assert_eq!(table.total, 16);
+        assert_eq!(table.functions["b"], FunctionCycles { flat: 10, cum: 10 });
+        assert_eq!(table.functions["a"], FunctionCycles { flat: 6, cum: 16 });
+        assert_eq!(table.functions["main"], FunctionCycles { flat: 0, cum: 16 });
+        assert_eq!(
+            table
+                .sorted(SortBy::Cum)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            ["a", "main", "b"]
+        );
+    }
+
+    #[test]
+    fn test_readme_profile() {
+        let profile = read_profile(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/test_invalid_block_969002.pb"
+        ))
+        .unwrap();
+        let table = CycleTable::new(&profile).unwrap();
+
+        assert_eq!(
+            table.sorted(SortBy::Flat)[0],
+            (
+                "substrate_bn::arith::U256::mul",
+                FunctionCycles {
+                    flat: 229411014,
+                    cum: 229411014
+                }
+            )
+        );
+        assert_eq!(
+            table.functions["cometbls_groth16_verifier::handle_verify_zkp_request"],
+            FunctionCycles {
+                flat: 277,
+                cum: 309731113
+            }
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let profile = profile();
+
+        assert_eq!(Profile::decode(profile.encode_to_vec().as_slice()).unwrap(), profile);
+    }
+}
@@ -0,0 +1,360 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, ensure, Context, Result};
+use cometbls_groth16_verifier::{abi::VerifiedHeader, VerifyZkpRequest};
+use methods::{
+    GUEST_CODE_FOR_ZK_PROOF_ABI_ELF, GUEST_CODE_FOR_ZK_PROOF_ABI_ID,
+    GUEST_CODE_FOR_ZK_PROOF_ACCELERATED_ELF, GUEST_CODE_FOR_ZK_PROOF_ACCELERATED_ID,
+    GUEST_CODE_FOR_ZK_PROOF_ELF, GUEST_CODE_FOR_ZK_PROOF_ID,
+};
+use risc0_zkvm::{
+    default_prover, get_prover_server, is_dev_mode, sha::Digest, ExecutorEnv, ExecutorEnvBuilder,
+    ExecutorImpl, ProveInfo, ProverOpts, Receipt, ReceiptKind, SessionStats, VerifierContext,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::ProofCache,
+    config::{self, current},
+    inspect::DecodedJournal,
+    results::Stats,
+};
+
+/// Proves `request` with the prover selected through the environment, see [`default_prover`].
+pub fn prove_request(request: &VerifyZkpRequest, opts: &ProverOpts) -> Result<ProveInfo> {
+    let env = env_builder()?.write(request)?.build()?;
+
+    default_prover().prove_with_opts(env, GUEST_CODE_FOR_ZK_PROOF_ELF, opts)
+}
+
+/// The builds of the guest, which only differ in the patched crates and the journal encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Guest {
+    Default,
+    /// sha2 uses the SHA-256 precompile.
+    AcceleratedSha,
+    /// Commits the verified header Solidity ABI encoded, see [`JournalEncoding::Abi`].
+    AbiJournal,
+}
+
+/// How a verifier guest commits its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalEncoding {
+    /// The digest of the request, see [`VerifyZkpRequest::digest`], and whether it is valid,
+    /// risc0 serde encoded.
+    Serde,
+    /// The verified header, see `VerifiedHeader::abi_encode`, or nothing if the request is
+    /// invalid.
+    Abi,
+}
+
+impl Guest {
+    pub fn elf(self) -> &'static [u8] {
+        match self {
+            Guest::Default => GUEST_CODE_FOR_ZK_PROOF_ELF,
+            Guest::AcceleratedSha => GUEST_CODE_FOR_ZK_PROOF_ACCELERATED_ELF,
+            Guest::AbiJournal => GUEST_CODE_FOR_ZK_PROOF_ABI_ELF,
+        }
+    }
+
+    pub fn image_id(self) -> [u32; 8] {
+        match self {
+            Guest::Default => GUEST_CODE_FOR_ZK_PROOF_ID,
+            Guest::AcceleratedSha => GUEST_CODE_FOR_ZK_PROOF_ACCELERATED_ID,
+            Guest::AbiJournal => GUEST_CODE_FOR_ZK_PROOF_ABI_ID,
+        }
+    }
+
+    pub fn journal_encoding(self) -> JournalEncoding {
+        match self {
+            Guest::Default | Guest::AcceleratedSha => JournalEncoding::Serde,
+            Guest::AbiJournal => JournalEncoding::Abi,
+        }
+    }
+
+    /// Whether `journal`, committed by this guest, proves that `request` is valid. Fails if it
+    /// commits the result of another request.
+    pub fn committed_validity(self, journal: &[u8], request: &VerifyZkpRequest) -> Result<bool> {
+        match DecodedJournal::decode_verifier(journal, self.journal_encoding())? {
+            DecodedJournal::Valid {
+                request_digest,
+                valid,
+            } => {
+                ensure!(
+                    request_digest == request.digest(),
+                    "the journal commits the result of another request"
+                );
+                Ok(valid)
+            }
+            DecodedJournal::Header(None) => Ok(false),
+            DecodedJournal::Header(Some(header)) => {
+                ensure!(
+                    VerifiedHeader::try_from(request).ok() == Some(header),
+                    "the journal commits another header than the one of the request"
+                );
+                Ok(true)
+            }
+            journal => bail!("not the journal of a verifier guest: {journal}"),
+        }
+    }
+
+    /// The guest with `image_id`, if it is one of the verifier guests.
+    pub fn from_image_id(image_id: impl Into<Digest>) -> Option<Self> {
+        let image_id = image_id.into();
+        [Guest::Default, Guest::AcceleratedSha, Guest::AbiJournal]
+            .into_iter()
+            .find(|guest| Digest::from(guest.image_id()) == image_id)
+    }
+}
+
+impl std::fmt::Display for Guest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Guest::Default => write!(f, "default"),
+            Guest::AcceleratedSha => write!(f, "accelerated-sha"),
+            Guest::AbiJournal => write!(f, "abi-journal"),
+        }
+    }
+}
+
+/// Like [`prove_request`], but returns the receipt from `cache` if `request` was proven with the
+/// same options before, and caches new receipts. The flag is whether the receipt was cached.
+pub fn prove_request_cached(
+    cache: &ProofCache,
+    request: &VerifyZkpRequest,
+    opts: &ProverOpts,
+) -> Result<(Receipt, Stats, bool)> {
+    let key = ProofCache::key(GUEST_CODE_FOR_ZK_PROOF_ID, request, opts, is_dev_mode())?;
+    if let Some((receipt, stats)) = cache.get(&key, GUEST_CODE_FOR_ZK_PROOF_ID)? {
+        return Ok((receipt, stats, true));
+    }
+
+    let prove_info = prove_request(request, opts)?;
+    let stats = Stats::from(&prove_info.stats);
+    cache.put(&key, &prove_info.receipt, &stats)?;
+    Ok((prove_info.receipt, stats, false))
+}
+
+/// Like [`prove_request`], but proving `guest` with segments of at most `2^segment_limit_po2`
+/// cycles instead of the default.
+pub fn prove_request_with(
+    request: &VerifyZkpRequest,
+    opts: &ProverOpts,
+    guest: Guest,
+    segment_limit_po2: u32,
+) -> Result<ProveInfo> {
+    let env = ExecutorEnv::builder()
+        .write(request)?
+        .segment_limit_po2(segment_limit_po2)
+        .build()?;
+
+    default_prover().prove_with_opts(env, guest.elf(), opts)
+}
+
+/// The result of [`prove_request_staged`].
+#[derive(Debug)]
+pub struct StagedProveInfo {
+    /// The receipt of the last stage `opts` asked for.
+    pub receipt: Receipt,
+    pub stats: SessionStats,
+    pub stages: Stages,
+}
+
+/// Wall-clock time and seal size of every proving stage, the later stages are only set when
+/// the requested receipt kind needs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Stages {
+    pub execute_millis: u64,
+    pub prove_segments_millis: u64,
+    pub lift_join_millis: Option<u64>,
+    pub groth16_millis: Option<u64>,
+    pub composite_seal_bytes: usize,
+    pub succinct_seal_bytes: Option<usize>,
+    pub groth16_seal_bytes: Option<usize>,
+}
+
+/// Proves `request` on the local prover one stage at a time: execution, proving the segments
+/// into a composite receipt, lifting and joining them into a succinct receipt and finally
+/// wrapping that into Groth16.
+///
+/// Bonsai runs all stages remotely, so this always proves locally (or fakes in dev mode).
+pub fn prove_request_staged(request: &VerifyZkpRequest, opts: &ProverOpts) -> Result<StagedProveInfo> {
+    let env = env_builder()?.write(request)?.build()?;
+    let prover = get_prover_server(&opts.clone().with_receipt_kind(ReceiptKind::Composite))?;
+
+    let start = Instant::now();
+    let session = ExecutorImpl::from_elf(env, GUEST_CODE_FOR_ZK_PROOF_ELF)?.run()?;
+    let execute = start.elapsed();
+
+    let start = Instant::now();
+    let ProveInfo { receipt, stats } = prover.prove_session(&VerifierContext::default(), &session)?;
+    let prove_segments = start.elapsed();
+
+    let mut stages = Stages {
+        execute_millis: millis(execute),
+        prove_segments_millis: millis(prove_segments),
+        lift_join_millis: None,
+        groth16_millis: None,
+        composite_seal_bytes: receipt.seal_size(),
+        succinct_seal_bytes: None,
+        groth16_seal_bytes: None,
+    };
+    if opts.receipt_kind == ReceiptKind::Composite {
+        return Ok(StagedProveInfo { receipt, stats, stages });
+    }
+
+    let start = Instant::now();
+    let receipt = prover.compress(&ProverOpts::succinct(), &receipt)?;
+    stages.lift_join_millis = Some(millis(start.elapsed()));
+    stages.succinct_seal_bytes = Some(receipt.seal_size());
+    if opts.receipt_kind == ReceiptKind::Succinct {
+        return Ok(StagedProveInfo { receipt, stats, stages });
+    }
+
+    let start = Instant::now();
+    let receipt = prover.compress(&ProverOpts::groth16(), &receipt)?;
+    stages.groth16_millis = Some(millis(start.elapsed()));
+    stages.groth16_seal_bytes = Some(receipt.seal_size());
+
+    Ok(StagedProveInfo { receipt, stats, stages })
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+/// The prover options of the [`current`] configuration.
+pub fn prover_opts() -> Result<ProverOpts> {
+    Ok(current()?.prover_opts())
+}
+
+/// An [`ExecutorEnv`] builder with the segment size of the [`current`] configuration.
+pub fn env_builder<'a>() -> Result<ExecutorEnvBuilder<'a>> {
+    let mut builder = ExecutorEnv::builder();
+    if let Some(po2) = current()?.segment_limit_po2 {
+        builder.segment_limit_po2(po2);
+    }
+    Ok(builder)
+}
+
+/// Where proofs are generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ProverBackend {
+    /// Proving on this machine.
+    Local,
+    /// Fake receipts, only for testing the configuration.
+    Dev,
+    /// A Bonsai compatible proving service, such as `mock-bonsai`.
+    Bonsai,
+}
+
+impl ProverBackend {
+    /// The backend [`default_prover`] picks from the environment.
+    pub fn from_env() -> Self {
+        if is_dev_mode() {
+            return ProverBackend::Dev;
+        }
+        match std::env::var("RISC0_PROVER").as_deref() {
+            Ok("local") => ProverBackend::Local,
+            Ok("bonsai") => ProverBackend::Bonsai,
+            _ if std::env::var("BONSAI_API_URL").is_ok() => ProverBackend::Bonsai,
+            _ => ProverBackend::Local,
+        }
+    }
+}
+
+impl std::fmt::Display for ProverBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProverBackend::Local => write!(f, "local"),
+            ProverBackend::Dev => write!(f, "dev"),
+            ProverBackend::Bonsai => write!(f, "bonsai"),
+        }
+    }
+}
+
+/// Explicit prover selection for the binaries, overriding the configuration file and the
+/// environment, see [`crate::config`].
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct ProverArgs {
+    /// Defaults to the selection through `RISC0_DEV_MODE` and `BONSAI_API_URL`.
+    #[arg(long, value_enum, global = true)]
+    pub prover: Option<ProverBackend>,
+    /// The URL of the Bonsai compatible service, defaults to `BONSAI_API_URL`.
+    #[arg(long, global = true)]
+    pub bonsai_url: Option<String>,
+    /// Defaults to `BONSAI_API_KEY`.
+    #[arg(long, global = true)]
+    pub bonsai_api_key: Option<String>,
+    #[arg(long, value_enum, global = true)]
+    pub receipt_kind: Option<config::ReceiptKind>,
+    /// Segments of at most `2^segment_limit_po2` cycles.
+    #[arg(long, global = true)]
+    pub segment_limit_po2: Option<u32>,
+}
+
+/// Selects `backend` for the rest of the process.
+///
+/// risc0-zkvm only takes the selection from the environment, so it is written there, which
+/// keeps [`default_prover`], `get_prover_server` and [`is_dev_mode`] consistent. Call this
+/// before spawning threads.
+pub fn select_backend(backend: ProverBackend, url: Option<&str>, api_key: Option<&str>) -> Result<()> {
+    match backend {
+        ProverBackend::Local => {
+            std::env::set_var("RISC0_DEV_MODE", "0");
+            std::env::set_var("RISC0_PROVER", "local");
+        }
+        ProverBackend::Dev => {
+            std::env::set_var("RISC0_DEV_MODE", "1");
+            std::env::remove_var("RISC0_PROVER");
+        }
+        ProverBackend::Bonsai => {
+            let url = url.context("the bonsai prover needs --bonsai-url or BONSAI_API_URL")?;
+            let api_key =
+                api_key.context("the bonsai prover needs --bonsai-api-key or BONSAI_API_KEY")?;
+            std::env::set_var("RISC0_DEV_MODE", "0");
+            std::env::set_var("RISC0_PROVER", "bonsai");
+            std::env::set_var("BONSAI_API_URL", url);
+            std::env::set_var("BONSAI_API_KEY", api_key);
+        }
+    }
+    Ok(())
+}
+
+/// The prover [`default_prover`] picks, as recorded in benchmark results.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProverMode {
+    pub backend: ProverBackend,
+    pub receipt_kind: config::ReceiptKind,
+}
+
+impl ProverMode {
+    /// The mode of the [`current`] configuration.
+    pub fn current() -> Result<Self> {
+        let config = current()?;
+        Ok(Self {
+            backend: config.prover.unwrap_or_else(ProverBackend::from_env),
+            receipt_kind: config.receipt_kind,
+        })
+    }
+}
+
+impl std::fmt::Display for ProverMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.receipt_kind {
+            config::ReceiptKind::Composite => write!(f, "{}", self.backend),
+            config::ReceiptKind::Succinct => write!(f, "{}+succinct", self.backend),
+            config::ReceiptKind::Groth16 => write!(f, "{}+groth16", self.backend),
+        }
+    }
+}
+
+pub fn is_enabled(option: &str) -> bool {
+    std::env::var(option)
+        .ok()
+        .map(|x| x.to_lowercase())
+        .filter(|x| x == "1" || x == "true" || x == "yes")
+        .is_some()
+}
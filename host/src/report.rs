@@ -0,0 +1,269 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate};
+
+/// A line of the results CSV written by [`crate::results::log_results`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultRow {
+    pub date: NaiveDate,
+    pub test: String,
+    pub duration_millis: u64,
+    pub segments: u64,
+    pub total_cycles: u64,
+    pub user_cycles: u64,
+    /// Empty in results written before it was recorded and when proving on Bonsai.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// The median of every column over the rows of a test on a single day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub runs: usize,
+    pub duration_millis: u64,
+    pub segments: u64,
+    pub total_cycles: u64,
+    pub user_cycles: u64,
+    /// The median of the rows that have it.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// All results of a single day, keyed by test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub date: NaiveDate,
+    pub tests: BTreeMap<String, Entry>,
+}
+
+pub fn read_results(path: impl AsRef<Path>) -> Result<Vec<ResultRow>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("unable to open {}", path.display()))?;
+    parse_results(BufReader::new(file)).with_context(|| format!("unable to parse {}", path.display()))
+}
+
+/// Columns are looked up by the header, so columns added to the CSV later are ignored.
+pub fn parse_results(reader: impl BufRead) -> Result<Vec<ResultRow>> {
+    let mut lines = reader.lines();
+    let header = lines.next().context("missing header")??;
+    let columns: Vec<_> = header.split(',').collect();
+    let column = |name: &str| {
+        columns
+            .iter()
+            .position(|column| *column == name)
+            .with_context(|| format!("missing column {name}"))
+    };
+    let [timestamp, test, duration_millis, segments, total_cycles, user_cycles] = [
+        column("timestamp")?,
+        column("test")?,
+        column("duration_millis")?,
+        column("segments")?,
+        column("total_cycles")?,
+        column("user_cycles")?,
+    ];
+    let peak_rss_bytes = column("peak_rss_bytes").ok();
+
+    lines
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.is_empty()))
+        .map(|(i, line)| {
+            let line = line?;
+            let fields: Vec<_> = line.split(',').collect();
+            if fields.len() != columns.len() {
+                bail!("line {}: expected {} fields, found {}", i + 2, columns.len(), fields.len());
+            }
+            let number = |index: usize| {
+                fields[index]
+                    .parse::<u64>()
+                    .with_context(|| format!("line {}: invalid number {}", i + 2, fields[index]))
+            };
+            Ok(ResultRow {
+                date: DateTime::parse_from_rfc3339(fields[timestamp])
+                    .with_context(|| format!("line {}: invalid timestamp", i + 2))?
+                    .date_naive(),
+                test: fields[test].to_owned(),
+                duration_millis: number(duration_millis)?,
+                segments: number(segments)?,
+                total_cycles: number(total_cycles)?,
+                user_cycles: number(user_cycles)?,
+                peak_rss_bytes: match peak_rss_bytes {
+                    Some(index) if !fields[index].is_empty() => Some(number(index)?),
+                    _ => None,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Groups the rows by day, ordered by date.
+pub fn group_runs(rows: &[ResultRow]) -> Vec<Run> {
+    let mut grouped = BTreeMap::<NaiveDate, BTreeMap<&str, Vec<&ResultRow>>>::new();
+    for row in rows {
+        grouped
+            .entry(row.date)
+            .or_default()
+            .entry(&row.test)
+            .or_default()
+            .push(row);
+    }
+
+    grouped
+        .into_iter()
+        .map(|(date, tests)| Run {
+            date,
+            tests: tests
+                .into_iter()
+                .map(|(test, rows)| {
+                    let median = |f: fn(&ResultRow) -> Option<u64>| {
+                        let mut values: Vec<_> = rows.iter().filter_map(|row| f(row)).collect();
+                        values.sort_unstable();
+                        values.get(values.len().checked_sub(1)? / 2).copied()
+                    };
+                    let entry = Entry {
+                        runs: rows.len(),
+                        duration_millis: median(|row| Some(row.duration_millis)).unwrap_or_default(),
+                        segments: median(|row| Some(row.segments)).unwrap_or_default(),
+                        total_cycles: median(|row| Some(row.total_cycles)).unwrap_or_default(),
+                        user_cycles: median(|row| Some(row.user_cycles)).unwrap_or_default(),
+                        peak_rss_bytes: median(|row| row.peak_rss_bytes),
+                    };
+                    (test.to_owned(), entry)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Renders a table per run in the format of the README, with the change relative to `baseline`
+/// next to every value when given. The peak memory is shown in MiB, `-` when it was not recorded.
+pub fn render_markdown(runs: &[Run], baseline: Option<&Run>) -> String {
+    let mut out = String::new();
+
+    for run in runs {
+        writeln!(out, "#### {}", run.date).unwrap();
+        if let Some(baseline) = baseline {
+            writeln!(out, "- Baseline: {}", baseline.date).unwrap();
+        }
+        writeln!(out).unwrap();
+
+        let width = run.tests.keys().map(String::len).max().unwrap_or(0).max(4);
+        let columns = ["duration_millis", "segments", "total_cycles", "user_cycles", "peak_rss_mib"];
+        let mut header = format!("| {:<width$} |", "test");
+        let mut separator = format!("|{}|", "-".repeat(width + 2));
+        let widths = columns.map(|column| column.len().max(12));
+        for (column, width) in columns.into_iter().zip(widths) {
+            write!(header, " {column:>width$} |").unwrap();
+            write!(separator, "{}:|", "-".repeat(width + 1)).unwrap();
+            if baseline.is_some() {
+                write!(header, " {:>8} |", "Δ").unwrap();
+                write!(separator, "{}:|", "-".repeat(9)).unwrap();
+            }
+        }
+        writeln!(out, "{header}").unwrap();
+        writeln!(out, "{separator}").unwrap();
+
+        for (test, entry) in &run.tests {
+            let base = baseline.and_then(|baseline| baseline.tests.get(test));
+            let mut line = format!("| {test:<width$} |");
+            for ((value, base_value), width) in [
+                (Some(entry.duration_millis), base.map(|b| b.duration_millis)),
+                (Some(entry.segments), base.map(|b| b.segments)),
+                (Some(entry.total_cycles), base.map(|b| b.total_cycles)),
+                (Some(entry.user_cycles), base.map(|b| b.user_cycles)),
+                (entry.peak_rss_bytes.map(mib), base.and_then(|b| b.peak_rss_bytes.map(mib))),
+            ]
+            .into_iter()
+            .zip(widths)
+            {
+                match value {
+                    Some(value) => write!(line, " {value:>width$} |").unwrap(),
+                    None => write!(line, " {:>width$} |", "-").unwrap(),
+                }
+                if baseline.is_some() {
+                    write!(line, " {:>8} |", delta(value, base_value)).unwrap();
+                }
+            }
+            writeln!(out, "{line}").unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    out
+}
+
+fn delta(value: Option<u64>, base: Option<u64>) -> String {
+    match (value, base) {
+        (None, _) | (_, None) => "n/a".to_owned(),
+        (Some(0), Some(0)) => "+0.00%".to_owned(),
+        (Some(_), Some(0)) => "n/a".to_owned(),
+        (Some(value), Some(base)) => {
+            format!("{:+.2}%", (value as f64 - base as f64) / base as f64 * 100.0)
+        }
+    }
+}
+
+fn mib(bytes: u64) -> u64 {
+    bytes / (1024 * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+timestamp,test,duration_millis,segments,total_cycles,user_cycles
+2024-10-02T10:00:00+00:00,valid_proof,200,325,340000768,309422908
+2024-10-02T11:00:00+00:00,valid_proof,100,325,340000768,309422908
+2024-10-02T12:00:00+00:00,valid_proof,300,325,340000768,309422908
+2024-10-03T10:00:00+00:00,valid_proof,150,324,339738624,309195187
+";
+
+    const CSV_WITH_PEAK_RSS: &str = "\
+timestamp,test,duration_millis,segments,total_cycles,user_cycles,peak_rss_bytes
+2024-10-04T10:00:00+00:00,valid_proof,200,325,340000768,309422908,
+2024-10-04T11:00:00+00:00,valid_proof,100,325,340000768,309422908,2147483648
+2024-10-04T12:00:00+00:00,valid_proof,300,325,340000768,309422908,1073741824
+2024-10-04T13:00:00+00:00,valid_proof,300,325,340000768,309422908,3221225472
+";
+
+    #[test]
+    fn test_group_runs() {
+        let runs = group_runs(&parse_results(CSV.as_bytes()).unwrap());
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(
+            runs[0].tests["valid_proof"],
+            Entry {
+                runs: 3,
+                duration_millis: 200,
+                segments: 325,
+                total_cycles: 340000768,
+                user_cycles: 309422908,
+                peak_rss_bytes: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_group_runs_peak_rss() {
+        let runs = group_runs(&parse_results(CSV_WITH_PEAK_RSS.as_bytes()).unwrap());
+
+        assert_eq!(runs[0].tests["valid_proof"].peak_rss_bytes, Some(2147483648));
+        assert!(render_markdown(&runs, None).contains("|         2048 |"));
+    }
+
+    #[test]
+    fn test_render_markdown_with_baseline() {
+        let runs = group_runs(&parse_results(CSV.as_bytes()).unwrap());
+
+        let markdown = render_markdown(&runs[1..], Some(&runs[0]));
+
+        assert!(markdown.starts_with("#### 2024-10-03\n- Baseline: 2024-10-02\n"));
+        assert!(markdown.contains("| valid_proof |             150 |  -25.00% |"));
+    }
+}
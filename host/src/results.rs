@@ -0,0 +1,155 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+    time::Duration,
+};
+
+use chrono::Utc;
+use fs2::FileExt;
+use risc0_zkvm::{SessionInfo, SessionStats};
+use serde::{Deserialize, Serialize};
+
+pub const CSV_HEADER: &str =
+    "timestamp,test,duration_millis,segments,total_cycles,user_cycles,peak_rss_bytes";
+
+/// The header of results written before `peak_rss_bytes` was recorded.
+const LEGACY_CSV_HEADER: &str = "timestamp,test,duration_millis,segments,total_cycles,user_cycles";
+
+/// Cycle counts of a guest run, as recorded in the results CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub segments: usize,
+    /// Cycles including the po2 padding of every segment.
+    pub total_cycles: u64,
+    pub user_cycles: u64,
+}
+
+impl From<&SessionStats> for Stats {
+    fn from(stats: &SessionStats) -> Self {
+        Self {
+            segments: stats.segments,
+            total_cycles: stats.total_cycles,
+            user_cycles: stats.user_cycles,
+        }
+    }
+}
+
+impl From<&SessionInfo> for Stats {
+    fn from(info: &SessionInfo) -> Self {
+        Self {
+            segments: info.segments.len(),
+            total_cycles: info.segments.iter().map(|segment| 1 << segment.po2).sum(),
+            user_cycles: info.cycles(),
+        }
+    }
+}
+
+/// Appends a line to the results CSV at `path`, writing the header if the file is new.
+///
+/// `peak_rss_bytes` is left empty when unknown, e.g. when proving on Bonsai or not on Linux.
+pub fn log_results(
+    path: impl AsRef<Path>,
+    test: &str,
+    stats: &Stats,
+    duration: Duration,
+    peak_rss_bytes: Option<u64>,
+) -> io::Result<()> {
+    // Open the file in append mode, creating it if it doesn't exist
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+
+    // Lock the file for exclusive access
+    file.lock_exclusive()?;
+
+    let metadata = file.metadata()?;
+    if metadata.len() == 0 {
+        writeln!(file, "{CSV_HEADER}")?;
+    } else {
+        upgrade(&mut file)?;
+    }
+
+    let line = format!(
+        "{},{},{},{},{},{},{}",
+        Utc::now().to_rfc3339(),
+        test,
+        duration.as_millis(),
+        stats.segments,
+        stats.total_cycles,
+        stats.user_cycles,
+        peak_rss_bytes.map(|bytes| bytes.to_string()).unwrap_or_default()
+    );
+
+    println!("{line}");
+    writeln!(file, "{}", line)?;
+
+    // Unlock the file after writing
+    file.unlock()?;
+
+    Ok(())
+}
+
+/// Adds the `peak_rss_bytes` column, empty, to results written with [`LEGACY_CSV_HEADER`].
+fn upgrade(file: &mut File) -> io::Result<()> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut header = String::new();
+    BufReader::new(&*file).read_line(&mut header)?;
+    let header = header.trim_end();
+    if header == CSV_HEADER {
+        return Ok(());
+    }
+    if header != LEGACY_CSV_HEADER {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected results header: {header}"),
+        ));
+    }
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    let mut upgraded = format!("{CSV_HEADER}\n");
+    for line in contents.lines().skip(1).filter(|line| !line.is_empty()) {
+        upgraded.push_str(line);
+        upgraded.push_str(",\n");
+    }
+
+    file.set_len(0)?;
+    file.write_all(upgraded.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_results_upgrades_legacy_header() {
+        let path = std::env::temp_dir().join(format!("results-{}.csv", std::process::id()));
+        std::fs::write(
+            &path,
+            format!("{LEGACY_CSV_HEADER}\n2024-10-02T10:00:00+00:00,valid_proof,200,325,340000768,309422908\n"),
+        )
+        .unwrap();
+
+        let stats = Stats {
+            segments: 325,
+            total_cycles: 340000768,
+            user_cycles: 309422908,
+        };
+        log_results(&path, "valid_proof", &stats, Duration::from_millis(100), Some(1024)).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<_> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "2024-10-02T10:00:00+00:00,valid_proof,200,325,340000768,309422908,"
+        );
+        assert!(lines[2].ends_with(",valid_proof,100,325,340000768,309422908,1024"));
+    }
+}
@@ -0,0 +1,371 @@
+//! A long-running proving service: requests are submitted over HTTP+JSON, queued, proven by a
+//! pool of workers and kept in a directory, so restarts do not lose jobs.
+//!
+//! | Method | Path                 | Response                                          |
+//! |--------|----------------------|---------------------------------------------------|
+//! | POST   | `/jobs`              | `202` and the queued [`Job`], the body is a [`FixtureRequest`] |
+//! | GET    | `/jobs`              | Every [`Job`], oldest first                       |
+//! | GET    | `/jobs/<id>`         | The [`Job`]                                       |
+//! | GET    | `/jobs/<id>/journal` | The hex encoded journal and the committed outcome |
+//! | GET    | `/jobs/<id>/receipt` | The bincode encoded [`Receipt`]                   |
+//!
+//! Errors are returned as `{"error": "<message>"}`.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Condvar, Mutex,
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use risc0_zkvm::Receipt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::ProofCache,
+    fixtures::FixtureRequest,
+    http::{path_segments, Response},
+    prove::{prove_request, prove_request_cached, prover_opts, Guest},
+    results::Stats,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded {
+        /// The outcome committed by the guest.
+        valid: bool,
+        #[serde(with = "hex")]
+        journal: Vec<u8>,
+        segments: usize,
+        total_cycles: u64,
+        user_cycles: u64,
+        duration_millis: u64,
+        /// Whether the receipt was taken from the proof cache.
+        #[serde(default)]
+        cached: bool,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub submitted_at: String,
+    pub finished_at: Option<String>,
+    #[serde(flatten)]
+    pub status: JobStatus,
+    pub request: FixtureRequest,
+}
+
+/// Every job is stored as `<id>.json`, the receipt of a finished job as `<id>.receipt`.
+#[derive(Debug, Clone)]
+pub struct JobStore {
+    dir: PathBuf,
+}
+
+impl JobStore {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).with_context(|| format!("unable to create {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// Writes to a temporary file first, so a crash never leaves a truncated job behind.
+    pub fn save(&self, job: &Job) -> Result<()> {
+        write_atomic(&self.dir.join(format!("{}.json", job.id)), &serde_json::to_vec_pretty(job)?)
+    }
+
+    pub fn save_receipt(&self, id: &str, receipt: &Receipt) -> Result<()> {
+        write_atomic(&self.receipt_path(id), &bincode::serialize(receipt)?)
+    }
+
+    /// The bincode encoded receipt, as served by the receipt endpoint.
+    pub fn read_receipt(&self, id: &str) -> Result<Vec<u8>> {
+        let path = self.receipt_path(id);
+        fs::read(&path).with_context(|| format!("unable to read {}", path.display()))
+    }
+
+    /// Every stored job, oldest first.
+    pub fn load_all(&self) -> Result<Vec<Job>> {
+        let mut jobs = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let contents = fs::read(&path)?;
+                jobs.push(
+                    serde_json::from_slice::<Job>(&contents)
+                        .with_context(|| format!("unable to parse {}", path.display()))?,
+                );
+            }
+        }
+        jobs.sort_by(|a, b| (&a.submitted_at, &a.id).cmp(&(&b.submitted_at, &b.id)));
+        Ok(jobs)
+    }
+
+    fn receipt_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.receipt"))
+    }
+}
+
+/// Writes through a temporary file next to `path`, unique to the process and the call, so writes
+/// of a job and its receipt never share one.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.{}.tmp", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, contents).with_context(|| format!("unable to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("unable to write {}", path.display()))?;
+    Ok(())
+}
+
+pub struct Service {
+    store: JobStore,
+    cache: Option<ProofCache>,
+    jobs: Mutex<BTreeMap<String, Job>>,
+    queue: Mutex<VecDeque<String>>,
+    queued: Condvar,
+}
+
+impl Service {
+    /// Loads the jobs in `store`. Jobs that were queued or running when the service stopped are
+    /// queued again, in the order they were submitted.
+    ///
+    /// With a `cache`, requests that were proven before are answered from it.
+    pub fn open(store: JobStore, cache: Option<ProofCache>) -> Result<Self> {
+        let mut jobs = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        for mut job in store.load_all()? {
+            if matches!(job.status, JobStatus::Queued | JobStatus::Running) {
+                job.status = JobStatus::Queued;
+                store.save(&job)?;
+                queue.push_back(job.id.clone());
+            }
+            jobs.insert(job.id.clone(), job);
+        }
+
+        Ok(Self {
+            store,
+            cache,
+            jobs: Mutex::new(jobs),
+            queue: Mutex::new(queue),
+            queued: Condvar::new(),
+        })
+    }
+
+    pub fn submit(&self, request: FixtureRequest) -> Result<Job> {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let mut nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+            while jobs.contains_key(&format!("{nanos:x}")) {
+                nanos += 1;
+            }
+            let job = Job {
+                id: format!("{nanos:x}"),
+                submitted_at: Utc::now().to_rfc3339(),
+                finished_at: None,
+                status: JobStatus::Queued,
+                request,
+            };
+            self.store.save(&job)?;
+            jobs.insert(job.id.clone(), job.clone());
+            job
+        };
+
+        self.queue.lock().unwrap().push_back(job.id.clone());
+        self.queued.notify_one();
+        Ok(job)
+    }
+
+    pub fn job(&self, id: &str) -> Option<Job> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    /// Every job, oldest first.
+    pub fn jobs(&self) -> Vec<Job> {
+        let mut jobs: Vec<_> = self.jobs.lock().unwrap().values().cloned().collect();
+        jobs.sort_by(|a, b| (&a.submitted_at, &a.id).cmp(&(&b.submitted_at, &b.id)));
+        jobs
+    }
+
+    /// Proves queued jobs forever, blocking while the queue is empty.
+    pub fn work(&self) {
+        loop {
+            let id = {
+                let mut queue = self.queue.lock().unwrap();
+                loop {
+                    match queue.pop_front() {
+                        Some(id) => break id,
+                        None => queue = self.queued.wait(queue).unwrap(),
+                    }
+                }
+            };
+            // A panicking prover fails the job instead of taking the worker down with it.
+            let result = panic::catch_unwind(AssertUnwindSafe(|| self.prove(&id)))
+                .unwrap_or_else(|_| {
+                    self.update(
+                        &id,
+                        JobStatus::Failed {
+                            error: "the prover panicked".to_owned(),
+                        },
+                        Some(Utc::now().to_rfc3339()),
+                    )
+                    .map(drop)
+                });
+            if let Err(err) = result {
+                eprintln!("job {id}: {err:#}");
+            }
+        }
+    }
+
+    fn prove(&self, id: &str) -> Result<()> {
+        let request = self.update(id, JobStatus::Running, None)?.request;
+
+        let request = request.into();
+        let opts = prover_opts()?;
+
+        let start = Instant::now();
+        let result = match &self.cache {
+            Some(cache) => prove_request_cached(cache, &request, &opts),
+            None => prove_request(&request, &opts)
+                .map(|prove_info| (prove_info.receipt, Stats::from(&prove_info.stats), false)),
+        };
+        let status = match result.and_then(|(receipt, stats, cached)| {
+            let valid = Guest::Default.committed_validity(&receipt.journal.bytes, &request)?;
+            self.store.save_receipt(id, &receipt)?;
+            Ok((valid, receipt, stats, cached))
+        }) {
+            Ok((valid, receipt, stats, cached)) => JobStatus::Succeeded {
+                valid,
+                journal: receipt.journal.bytes,
+                segments: stats.segments,
+                total_cycles: stats.total_cycles,
+                user_cycles: stats.user_cycles,
+                duration_millis: start.elapsed().as_millis() as u64,
+                cached,
+            },
+            Err(err) => JobStatus::Failed {
+                error: format!("{err:#}"),
+            },
+        };
+
+        self.update(id, status, Some(Utc::now().to_rfc3339()))?;
+        Ok(())
+    }
+
+    fn update(&self, id: &str, status: JobStatus, finished_at: Option<String>) -> Result<Job> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.get_mut(id).with_context(|| format!("unknown job {id}"))?;
+        job.status = status;
+        job.finished_at = finished_at;
+        self.store.save(job)?;
+        Ok(job.clone())
+    }
+
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {
+        match (method, path_segments(path).as_slice()) {
+            ("POST", ["jobs"]) => match serde_json::from_slice::<FixtureRequest>(body) {
+                Ok(request) => match self.submit(request) {
+                    Ok(job) => Response::json(202, &job),
+                    Err(err) => Response::error(500, format!("{err:#}")),
+                },
+                Err(err) => Response::error(400, format!("invalid request: {err}")),
+            },
+            ("GET", ["jobs"]) => Response::json(200, &self.jobs()),
+            ("GET", ["jobs", id, rest @ ..]) => {
+                let Some(job) = self.job(id) else {
+                    return Response::error(404, format!("unknown job {id}"));
+                };
+                match (rest, &job.status) {
+                    ([], _) => Response::json(200, &job),
+                    (["journal"], JobStatus::Succeeded { valid, journal, .. }) => Response::json(
+                        200,
+                        &serde_json::json!({ "valid": valid, "journal": hex::encode(journal) }),
+                    ),
+                    (["receipt"], JobStatus::Succeeded { .. }) => {
+                        match self.store.read_receipt(id) {
+                            Ok(receipt) => Response::bytes(receipt),
+                            Err(err) => Response::error(500, format!("{err:#}")),
+                        }
+                    }
+                    (["journal" | "receipt"], _) => {
+                        Response::error(409, format!("job {id} has not succeeded"))
+                    }
+                    _ => Response::error(404, format!("not found: {path}")),
+                }
+            }
+            _ => Response::error(404, format!("not found: {method} {path}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{default_fixture_dir, load_fixtures};
+
+    fn store(name: &str) -> JobStore {
+        let dir = std::env::temp_dir().join(format!("service-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        JobStore::open(dir).unwrap()
+    }
+
+    fn request() -> FixtureRequest {
+        load_fixtures(default_fixture_dir()).unwrap()[0].request.clone()
+    }
+
+    #[test]
+    fn test_submit_and_status() {
+        let service = Service::open(store("submit"), None).unwrap();
+
+        let body = serde_json::to_vec(&request()).unwrap();
+        let response = service.handle("POST", "/jobs", &body);
+        assert_eq!(response.status, 202);
+        let job: Job = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+
+        let response = service.handle("GET", &format!("/jobs/{}", job.id), &[]);
+        assert_eq!(serde_json::from_slice::<Job>(&response.body).unwrap(), job);
+        assert_eq!(
+            service.handle("GET", &format!("/jobs/{}/receipt", job.id), &[]).status,
+            409
+        );
+        assert_eq!(service.handle("GET", "/jobs/unknown", &[]).status, 404);
+        assert_eq!(service.handle("POST", "/jobs", b"{}").status, 400);
+    }
+
+    #[test]
+    fn test_restart_requeues_unfinished_jobs() {
+        let store = store("restart");
+        let service = Service::open(store.clone(), None).unwrap();
+        let queued = service.submit(request()).unwrap();
+        let running = service.submit(request()).unwrap();
+        service.update(&running.id, JobStatus::Running, None).unwrap();
+        let failed = service.submit(request()).unwrap();
+        let failed = service
+            .update(&failed.id, JobStatus::Failed { error: "error".into() }, None)
+            .unwrap();
+
+        let restarted = Service::open(store, None).unwrap();
+
+        assert_eq!(
+            *restarted.queue.lock().unwrap(),
+            [queued.id, running.id.clone()]
+        );
+        assert_eq!(restarted.job(&running.id).unwrap().status, JobStatus::Queued);
+        assert_eq!(restarted.job(&failed.id).unwrap(), failed);
+    }
+}
@@ -0,0 +1,228 @@
+//! Proves every fixture with every combination of segment size and guest build, to pick the
+//! fastest configuration for the CometBLS guest.
+
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+    time::Instant,
+};
+
+use anyhow::{ensure, Result};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{
+    bench::{MachineInfo, Summary},
+    fixtures::Fixture,
+    memory::track_peak_rss,
+    prove::{prove_request_with, prover_opts, Guest, ProverMode},
+    results::Stats,
+};
+
+/// A complete sweep, written as JSON.
+#[derive(Debug, Serialize)]
+pub struct SweepReport {
+    pub started_at: String,
+    pub machine: MachineInfo,
+    pub prover: ProverMode,
+    pub warmup: usize,
+    pub iterations: usize,
+    pub results: Vec<SweepResult>,
+}
+
+/// The measurements of a fixture with a single configuration.
+#[derive(Debug, Serialize)]
+pub struct SweepResult {
+    pub test: String,
+    pub segment_limit_po2: u32,
+    pub guest: Guest,
+    pub summary: Summary,
+    pub segments: usize,
+    pub total_cycles: u64,
+    pub user_cycles: u64,
+    /// The highest peak resident memory of the measured iterations, `None` when not on Linux.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// Proves `fixture` `warmup + iterations` times with `guest` and `segment_limit_po2`, only the
+/// last `iterations` are measured.
+pub fn run_config(
+    fixture: &Fixture,
+    guest: Guest,
+    segment_limit_po2: u32,
+    warmup: usize,
+    iterations: usize,
+) -> Result<SweepResult> {
+    ensure!(iterations > 0, "at least one iteration is required");
+
+    let opts = prover_opts()?;
+    let mut durations = Vec::with_capacity(iterations);
+    let mut peak_rss = None;
+    let mut stats = None;
+
+    for i in 0..warmup + iterations {
+        let request = fixture.request();
+        let start = Instant::now();
+        let (prove_info, run_peak_rss) =
+            track_peak_rss(|| prove_request_with(&request, &opts, guest, segment_limit_po2));
+        let prove_info = prove_info?;
+        let duration = start.elapsed();
+
+        let valid = guest.committed_validity(&prove_info.receipt.journal.bytes, &request)?;
+        ensure!(
+            valid == fixture.expected.valid,
+            "{} ({guest}, po2 {segment_limit_po2}): guest committed {valid}, expected {}",
+            fixture.name,
+            fixture.expected.valid
+        );
+
+        if i < warmup {
+            continue;
+        }
+        peak_rss = peak_rss.max(run_peak_rss);
+        durations.push(duration);
+        stats.get_or_insert(Stats::from(&prove_info.stats));
+    }
+
+    let stats = stats.expect("at least one iteration is measured; qed;");
+    Ok(SweepResult {
+        test: fixture.name.clone(),
+        segment_limit_po2,
+        guest,
+        summary: Summary::new(&durations),
+        segments: stats.segments,
+        total_cycles: stats.total_cycles,
+        user_cycles: stats.user_cycles,
+        peak_rss_bytes: peak_rss,
+    })
+}
+
+/// Runs every fixture with every combination of `guests` and `segment_limit_po2s`.
+pub fn run(
+    fixtures: &[Fixture],
+    guests: &[Guest],
+    segment_limit_po2s: &[u32],
+    warmup: usize,
+    iterations: usize,
+) -> Result<SweepReport> {
+    let started_at = Utc::now().to_rfc3339();
+    let mut results = Vec::new();
+    for fixture in fixtures {
+        for &guest in guests {
+            for &po2 in segment_limit_po2s {
+                results.push(run_config(fixture, guest, po2, warmup, iterations)?);
+            }
+        }
+    }
+
+    Ok(SweepReport {
+        started_at,
+        machine: MachineInfo::detect(),
+        prover: ProverMode::current()?,
+        warmup,
+        iterations,
+        results,
+    })
+}
+
+impl SweepReport {
+    pub fn write_json(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// One line per fixture and configuration, overwriting `path`.
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut file = File::create(path)?;
+        writeln!(
+            file,
+            "started_at,prover,test,guest,segment_limit_po2,iterations,min_millis,median_millis,p95_millis,\
+             segments,total_cycles,user_cycles,peak_rss_bytes"
+        )?;
+        for result in &self.results {
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                self.started_at,
+                self.prover,
+                result.test,
+                result.guest,
+                result.segment_limit_po2,
+                self.iterations,
+                result.summary.min_millis,
+                result.summary.median_millis,
+                result.summary.p95_millis,
+                result.segments,
+                result.total_cycles,
+                result.user_cycles,
+                result.peak_rss_bytes.map(|bytes| bytes.to_string()).unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The configuration with the lowest median duration per test.
+    pub fn fastest(&self) -> Vec<&SweepResult> {
+        let mut fastest: Vec<&SweepResult> = Vec::new();
+        for result in &self.results {
+            match fastest.iter_mut().find(|best| best.test == result.test) {
+                Some(best) if result.summary.median_millis < best.summary.median_millis => {
+                    *best = result
+                }
+                Some(_) => {}
+                None => fastest.push(result),
+            }
+        }
+        fastest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::ReceiptKind, prove::ProverBackend};
+
+    #[test]
+    fn test_fastest() {
+        let result = |test: &str, segment_limit_po2, median_millis| SweepResult {
+            test: test.to_owned(),
+            segment_limit_po2,
+            guest: Guest::Default,
+            summary: Summary {
+                min_millis: median_millis,
+                median_millis,
+                p95_millis: median_millis,
+            },
+            segments: 1,
+            total_cycles: 1,
+            user_cycles: 1,
+            peak_rss_bytes: None,
+        };
+        let report = SweepReport {
+            started_at: String::new(),
+            machine: MachineInfo::detect(),
+            prover: ProverMode {
+                backend: ProverBackend::Local,
+                receipt_kind: ReceiptKind::Succinct,
+            },
+            warmup: 0,
+            iterations: 1,
+            results: vec![
+                result("valid_proof", 19, 300),
+                result("valid_proof", 20, 200),
+                result("valid_proof", 21, 250),
+                result("invalid_proof", 20, 100),
+            ],
+        };
+
+        assert_eq!(
+            report
+                .fastest()
+                .into_iter()
+                .map(|best| (best.test.as_str(), best.segment_limit_po2))
+                .collect::<Vec<_>>(),
+            [("valid_proof", 20), ("invalid_proof", 20)]
+        );
+    }
+}
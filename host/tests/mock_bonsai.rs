@@ -0,0 +1,58 @@
+//! Proves a fixture through `BonsaiProver` against the mock Bonsai server.
+
+use std::{sync::Arc, thread};
+
+use host::{
+    fixtures::{default_fixture_dir, load_fixtures},
+    http,
+    mock_bonsai::MockBonsai,
+    prove::Guest,
+};
+use methods::GUEST_CODE_FOR_ZK_PROOF_ELF;
+use risc0_zkvm::{BonsaiProver, ExecutorEnv, Prover, ProverOpts};
+
+#[test]
+fn test_prove_with_mock_bonsai() {
+    // The server proves in the same process, so it shares the mode with the client. Set
+    // `RISC0_DEV_MODE=0` to prove for real.
+    if std::env::var("RISC0_DEV_MODE").is_err() {
+        std::env::set_var("RISC0_DEV_MODE", "1");
+    }
+
+    let server = http::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr());
+    let bonsai = Arc::new(MockBonsai::new(&url));
+    {
+        let bonsai = bonsai.clone();
+        thread::spawn(move || bonsai.work());
+    }
+    thread::spawn(move || {
+        http::serve(&server, |method, url, body| {
+            bonsai.handle(method, url, body)
+        })
+    });
+
+    std::env::set_var("BONSAI_API_URL", &url);
+    std::env::set_var("BONSAI_API_KEY", "mock");
+
+    let fixture = load_fixtures(default_fixture_dir())
+        .unwrap()
+        .into_iter()
+        .next()
+        .expect("no fixtures found");
+    let env = ExecutorEnv::builder()
+        .write(&fixture.request())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // Not `default_prover`, which ignores Bonsai in dev mode.
+    let prove_info = BonsaiProver::new("mock-bonsai")
+        .prove_with_opts(env, GUEST_CODE_FOR_ZK_PROOF_ELF, &ProverOpts::succinct())
+        .unwrap();
+
+    let valid = Guest::Default
+        .committed_validity(&prove_info.receipt.journal.bytes, &fixture.request())
+        .unwrap();
+    assert_eq!(valid, fixture.expected.valid, "{}", fixture.name);
+}
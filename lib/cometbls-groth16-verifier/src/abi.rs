@@ -0,0 +1,257 @@
+//! Solidity ABI encodings, for handing verification results to EVM contracts and reading the
+//! proofs they receive.
+
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+
+use byteorder::BigEndian;
+use ethabi::{ParamType, Token};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    handle_verify_zkp_request, Error, RawZKP, VerifyZkpRequest, EXPECTED_PROOF_SIZE,
+    FQ_SIZE, H256, NANOS_PER_SECOND, ZKP,
+};
+
+/// The header a successful verification vouches for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedHeader {
+    pub chain_id: String,
+    pub height: u64,
+    /// Nanoseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub validators_hash: H256,
+    pub next_validators_hash: H256,
+    pub app_hash: H256,
+}
+
+impl TryFrom<&VerifyZkpRequest> for VerifiedHeader {
+    type Error = Error;
+
+    fn try_from(request: &VerifyZkpRequest) -> Result<Self, Self::Error> {
+        let seconds = u64::try_from(request.seconds).map_err(|_| Error::InvalidTimestamp)?;
+        let nanos = u64::try_from(request.nanos)
+            .ok()
+            .filter(|nanos| *nanos < NANOS_PER_SECOND as u64)
+            .ok_or(Error::InvalidTimestamp)?;
+        let timestamp = seconds
+            .checked_mul(NANOS_PER_SECOND as u64)
+            .and_then(|timestamp| timestamp.checked_add(nanos))
+            .ok_or(Error::InvalidTimestamp)?;
+
+        Ok(Self {
+            chain_id: request.chain_id.clone(),
+            height: request.height.try_into().map_err(|_| Error::InvalidHeight)?,
+            timestamp,
+            validators_hash: request.validators_hash,
+            next_validators_hash: request.next_validators_hash,
+            app_hash: request.app_hash,
+        })
+    }
+}
+
+impl VerifiedHeader {
+    /// Verifies `request` like [`handle_verify_zkp_request`], returning the header it vouches for.
+    pub fn verify(request: VerifyZkpRequest) -> Result<Self, Error> {
+        let header = Self::try_from(&request)?;
+        handle_verify_zkp_request(request)?;
+        Ok(header)
+    }
+
+    /// The parameter types of [`VerifiedHeader::abi_encode`].
+    pub const ABI_TYPES: [ParamType; 6] = [
+        ParamType::String,
+        ParamType::Uint(64),
+        ParamType::Uint(64),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+    ];
+
+    /// `abi.encode(chainId, height, timestamp, validatorsHash, nextValidatorsHash, appHash)`, as
+    /// `(string, uint64, uint64, bytes32, bytes32, bytes32)`.
+    pub fn abi_encode(&self) -> Vec<u8> {
+        ethabi::encode(&[
+            Token::String(self.chain_id.clone()),
+            Token::Uint(self.height.into()),
+            Token::Uint(self.timestamp.into()),
+            Token::FixedBytes(self.validators_hash.to_vec()),
+            Token::FixedBytes(self.next_validators_hash.to_vec()),
+            Token::FixedBytes(self.app_hash.to_vec()),
+        ])
+    }
+
+    /// The inverse of [`VerifiedHeader::abi_encode`], only accepting its exact output.
+    pub fn abi_decode(bytes: &[u8]) -> Result<Self, Error> {
+        let tokens = ethabi::decode(&Self::ABI_TYPES, bytes).map_err(|_| Error::InvalidAbi)?;
+        let [chain_id, height, timestamp, validators_hash, next_validators_hash, app_hash] =
+            <[Token; 6]>::try_from(tokens).map_err(|_| Error::InvalidAbi)?;
+
+        let uint64 = |token: Token| match token {
+            Token::Uint(value) if value.bits() <= 64 => Ok(value.low_u64()),
+            _ => Err(Error::InvalidAbi),
+        };
+        let bytes32 = |token: Token| match token {
+            Token::FixedBytes(bytes) => H256::try_from(bytes).map_err(|_| Error::InvalidAbi),
+            _ => Err(Error::InvalidAbi),
+        };
+        let header = Self {
+            chain_id: match chain_id {
+                Token::String(chain_id) => chain_id,
+                _ => return Err(Error::InvalidAbi),
+            },
+            height: uint64(height)?,
+            timestamp: uint64(timestamp)?,
+            validators_hash: bytes32(validators_hash)?,
+            next_validators_hash: bytes32(next_validators_hash)?,
+            app_hash: bytes32(app_hash)?,
+        };
+
+        // `ethabi::decode` ignores trailing bytes and dirty padding.
+        if header.abi_encode() != bytes {
+            return Err(Error::InvalidAbi);
+        }
+        Ok(header)
+    }
+}
+
+impl ZKP<BigEndian> {
+    /// The parameter types of [`ZKP::abi_encode`].
+    pub fn abi_types() -> [ParamType; 3] {
+        let uint256_array = |len| ParamType::FixedArray(Box::new(ParamType::Uint(256)), len);
+        [uint256_array(8), uint256_array(2), uint256_array(2)]
+    }
+
+    /// `abi.encode(proof, proofCommitment, proofCommitmentPOK)`, as
+    /// `(uint256[8], uint256[2], uint256[2])`, the `zkp` the EVM light client decodes.
+    ///
+    /// `proof` is `[a.x, a.y, b.x.c1, b.x.c0, b.y.c1, b.y.c0, c.x, c.y]`, with `G2` coordinates
+    /// ordered like the pairing precompile takes them. Fails unless the proof has exactly one
+    /// commitment, like CometBLS proofs.
+    pub fn abi_encode(&self) -> Result<Vec<u8>, Error> {
+        let (proof_commitment, proof_commitment_pok) = self.cometbls_commitment()?;
+        let fq = |fq: substrate_bn::Fq| {
+            let mut bytes = [0; FQ_SIZE];
+            fq.to_big_endian(&mut bytes).expect("an Fq is 32 bytes; qed;");
+            Token::Uint(ethabi::Uint::from_big_endian(&bytes))
+        };
+        let g1 = |point: &substrate_bn::AffineG1| [fq(point.x()), fq(point.y())];
+        let g2 = |point: &substrate_bn::AffineG2| {
+            [
+                fq(point.x().imaginary()),
+                fq(point.x().real()),
+                fq(point.y().imaginary()),
+                fq(point.y().real()),
+            ]
+        };
+
+        let proof = g1(&self.proof.a)
+            .into_iter()
+            .chain(g2(&self.proof.b))
+            .chain(g1(&self.proof.c))
+            .collect();
+        Ok(ethabi::encode(&[
+            Token::FixedArray(proof),
+            Token::FixedArray(g1(proof_commitment).to_vec()),
+            Token::FixedArray(g1(proof_commitment_pok).to_vec()),
+        ]))
+    }
+
+    /// The inverse of [`ZKP::abi_encode`], only accepting its exact output.
+    ///
+    /// The static arrays are encoded in place, so the words are the raw big endian proof
+    /// [`ZKP::try_from`] decodes.
+    pub fn abi_decode(bytes: &[u8]) -> Result<Self, Error> {
+        // `ethabi::decode` ignores trailing bytes.
+        if bytes.len() != EXPECTED_PROOF_SIZE {
+            return Err(Error::InvalidAbi);
+        }
+        let tokens = ethabi::decode(&Self::abi_types(), bytes).map_err(|_| Error::InvalidAbi)?;
+
+        let mut raw: RawZKP = [0; EXPECTED_PROOF_SIZE];
+        let words = tokens.into_iter().flat_map(|token| match token {
+            Token::FixedArray(words) => words,
+            _ => vec![],
+        });
+        for (word, chunk) in words.zip(raw.chunks_exact_mut(FQ_SIZE)) {
+            match word {
+                Token::Uint(word) => word.to_big_endian(chunk),
+                _ => return Err(Error::InvalidAbi),
+            }
+        }
+        Self::try_from(&raw[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::fixture_zkp;
+
+    fn header() -> VerifiedHeader {
+        VerifiedHeader {
+            chain_id: "union-devnet-1337".into(),
+            height: 969001,
+            timestamp: 1_727_858_000_123_456_789,
+            validators_hash: [1; 32],
+            next_validators_hash: [2; 32],
+            app_hash: [3; 32],
+        }
+    }
+
+    #[test]
+    fn test_abi_round_trip() {
+        let header = header();
+        let encoded = header.abi_encode();
+        // Six head words, then the length and the padded chain ID.
+        assert_eq!(encoded.len(), 6 * 32 + 32 + 32);
+        assert_eq!(&encoded[..32], &ethabi::encode(&[Token::Uint(192u64.into())])[..]);
+        assert_eq!(VerifiedHeader::abi_decode(&encoded), Ok(header));
+    }
+
+    #[test]
+    fn test_abi_decode_rejects_invalid() {
+        let encoded = header().abi_encode();
+        assert_eq!(VerifiedHeader::abi_decode(&encoded[..64]), Err(Error::InvalidAbi));
+
+        let mut too_large = encoded.clone();
+        too_large[32 + 23] = 1;
+        assert_eq!(VerifiedHeader::abi_decode(&too_large), Err(Error::InvalidAbi));
+
+        let mut trailing = encoded.clone();
+        trailing.extend([0; 32]);
+        assert_eq!(VerifiedHeader::abi_decode(&trailing), Err(Error::InvalidAbi));
+    }
+
+    #[test]
+    fn test_zkp_abi_round_trip() {
+        let zkp = fixture_zkp();
+        let decoded = ZKP::<BigEndian>::abi_decode(&zkp).unwrap();
+        assert_eq!(decoded.abi_encode().unwrap(), zkp);
+        assert_eq!(ZKP::<BigEndian>::try_from(&zkp[..]).unwrap().abi_encode().unwrap(), zkp);
+
+        let mut without_commitment = decoded;
+        without_commitment.proof_commitments.clear();
+        assert!(matches!(without_commitment.abi_encode(), Err(Error::InvalidCommitment)));
+    }
+
+    #[test]
+    fn test_zkp_abi_decode_rejects_invalid() {
+        let zkp = fixture_zkp().to_vec();
+        assert!(matches!(
+            ZKP::<BigEndian>::abi_decode(&zkp[..EXPECTED_PROOF_SIZE - 32]),
+            Err(Error::InvalidAbi)
+        ));
+
+        let mut trailing = zkp.clone();
+        trailing.extend([0; 32]);
+        assert!(matches!(ZKP::<BigEndian>::abi_decode(&trailing), Err(Error::InvalidAbi)));
+
+        // A coordinate larger than the base field modulus.
+        let mut out_of_field = zkp.clone();
+        out_of_field[..FQ_SIZE].fill(0xff);
+        assert!(matches!(
+            ZKP::<BigEndian>::abi_decode(&out_of_field),
+            Err(Error::InvalidPoint)
+        ));
+    }
+}
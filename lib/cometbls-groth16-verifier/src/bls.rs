@@ -0,0 +1,194 @@
+//! gnark compatible Groth16 verification over BLS12-381, the counterpart of [`crate::groth16`].
+//!
+//! Points use gnark's uncompressed encoding, which for BLS12-381 is the one of ZCash: big endian
+//! coordinates, `G2` coordinates imaginary part first, and the point at infinity flagged in the
+//! top bits of the first byte.
+
+use alloc::vec::Vec;
+
+use bls12_381::{G1Affine, G2Affine, Scalar};
+
+use crate::{
+    curve::{Bls12_381, Curve},
+    groth16::{self, expand_message_xmd},
+    Error,
+};
+
+pub const FQ_SIZE: usize = Bls12_381::FQ_SIZE;
+pub const G1_SIZE: usize = Bls12_381::G1_SIZE;
+pub const G2_SIZE: usize = Bls12_381::G2_SIZE;
+
+/// The key the proof of knowledge of the Pedersen commitments is verified with.
+pub type CommitmentKey = groth16::CommitmentKey<Bls12_381>;
+/// A verification key in the Groth16 SNARK, see [`crate::VerifyingKey`].
+pub type VerifyingKey = groth16::VerifyingKey<Bls12_381>;
+pub type Proof = groth16::Proof<Bls12_381>;
+
+pub struct ZKP {
+    pub proof: Proof,
+    pub proof_commitments: Vec<G1Affine>,
+    /// The proof of knowledge of all commitments, `None` without commitments.
+    pub proof_commitment_pok: Option<G1Affine>,
+}
+
+/// `a ‖ b ‖ c`, followed by the commitments and their proof of knowledge if there are any. The
+/// number of commitments is implied by the length, see [`Curve::proof_commitments`].
+impl TryFrom<&[u8]> for ZKP {
+    type Error = Error;
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let commitments =
+            Bls12_381::proof_commitments(value.len()).ok_or(Error::InvalidRawProof)?;
+        let (a, rest) = value.split_at(G1_SIZE);
+        let (b, rest) = rest.split_at(G2_SIZE);
+        let (c, rest) = rest.split_at(G1_SIZE);
+        let mut points = rest.chunks_exact(G1_SIZE).map(decode_g1);
+
+        let proof = Proof {
+            a: decode_g1(a)?,
+            b: decode_g2(b)?,
+            c: decode_g1(c)?,
+        };
+        let proof_commitments = points.by_ref().take(commitments).collect::<Result<_, _>>()?;
+        let proof_commitment_pok = points.next().transpose()?;
+        Ok(Self {
+            proof,
+            proof_commitments,
+            proof_commitment_pok,
+        })
+    }
+}
+
+/// Decodes an uncompressed `G1` point, checking it is in the subgroup.
+pub fn decode_g1(bytes: &[u8]) -> Result<G1Affine, Error> {
+    let bytes = <&[u8; G1_SIZE]>::try_from(bytes).map_err(|_| Error::InvalidSliceLength)?;
+    Option::from(G1Affine::from_uncompressed(bytes)).ok_or(Error::InvalidPoint)
+}
+
+/// Decodes an uncompressed `G2` point, checking it is in the subgroup.
+pub fn decode_g2(bytes: &[u8]) -> Result<G2Affine, Error> {
+    let bytes = <&[u8; G2_SIZE]>::try_from(bytes).map_err(|_| Error::InvalidSliceLength)?;
+    Option::from(G2Affine::from_uncompressed(bytes)).ok_or(Error::InvalidPoint)
+}
+
+/// Decodes a big endian scalar, which must be reduced.
+pub fn decode_scalar(bytes: [u8; 32]) -> Result<Scalar, Error> {
+    let mut le = bytes;
+    le.reverse();
+    Option::from(Scalar::from_bytes(&le)).ok_or(Error::InvalidPublicInput)
+}
+
+/// Verifies `zkp` for `public_inputs`, the same way [`crate::verify_groth16`] verifies BN254
+/// proofs.
+pub fn verify_groth16(
+    vk: &VerifyingKey,
+    zkp: &ZKP,
+    public_inputs: &[Scalar],
+) -> Result<(), Error> {
+    groth16::verify(
+        vk,
+        &zkp.proof,
+        &zkp.proof_commitments,
+        zkp.proof_commitment_pok,
+        public_inputs,
+    )
+}
+
+/// gnark's `fr.Hash(message, dst, 1)` over the BLS12-381 scalar field.
+pub fn hash_to_scalar(message: &[u8], dst: &[u8]) -> Scalar {
+    let mut bytes = [0; 48];
+    expand_message_xmd(message, dst, &mut bytes);
+    let mut wide = [0; 64];
+    for (le, be) in wide.iter_mut().zip(bytes.iter().rev()) {
+        *le = *be;
+    }
+    Scalar::from_bytes_wide(&wide)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::tests::{groth16_instance, Instance, KnownLogs};
+
+    fn instance(public_inputs: &[Scalar], committed: Vec<Vec<usize>>) -> (VerifyingKey, ZKP) {
+        let Instance {
+            vk,
+            proof,
+            commitments,
+            pok,
+        } = groth16_instance::<Bls12_381>(public_inputs, committed);
+        let zkp = ZKP {
+            proof,
+            proof_commitments: commitments,
+            proof_commitment_pok: pok,
+        };
+        (vk, zkp)
+    }
+
+    fn encode(zkp: &ZKP) -> Vec<u8> {
+        let mut bytes = zkp.proof.a.to_uncompressed().to_vec();
+        bytes.extend(zkp.proof.b.to_uncompressed());
+        bytes.extend(zkp.proof.c.to_uncompressed());
+        for point in zkp.proof_commitments.iter().chain(&zkp.proof_commitment_pok) {
+            bytes.extend(point.to_uncompressed());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_verify_groth16() {
+        let inputs = [1, 2, 3].map(Scalar::from);
+        let (vk, zkp) = instance(&inputs, vec![]);
+        assert_eq!(verify_groth16(&vk, &zkp, &inputs), Ok(()));
+        assert_eq!(
+            verify_groth16(&vk, &zkp, &[1, 2, 4].map(Scalar::from)),
+            Err(Error::InvalidProof)
+        );
+        assert_eq!(
+            verify_groth16(&vk, &zkp, &inputs[..2]),
+            Err(Error::InvalidPublicInput)
+        );
+    }
+
+    #[test]
+    fn test_verify_groth16_commitments() {
+        let inputs = [1, 2].map(Scalar::from);
+        for committed in [vec![vec![]], vec![vec![1], vec![0, 1]]] {
+            let (vk, zkp) = instance(&inputs, committed);
+            assert_eq!(verify_groth16(&vk, &zkp, &inputs), Ok(()));
+
+            let decoded = ZKP::try_from(&encode(&zkp)[..]).unwrap();
+            assert_eq!(verify_groth16(&vk, &decoded, &inputs), Ok(()));
+
+            let mut tampered = zkp;
+            tampered.proof_commitment_pok = Some(Bls12_381::g1(Scalar::from(5)));
+            assert_eq!(verify_groth16(&vk, &tampered, &inputs), Err(Error::InvalidPok));
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let (_, zkp) = instance(&[Scalar::one()], vec![vec![0]]);
+        let bytes = encode(&zkp);
+        assert_eq!(bytes.len(), Bls12_381::PROOF_SIZE);
+        assert_eq!(
+            ZKP::try_from(&bytes[..bytes.len() - 1]).err(),
+            Some(Error::InvalidRawProof)
+        );
+
+        // Not on the curve.
+        let mut tampered = bytes.clone();
+        tampered[G1_SIZE - 1] ^= 1;
+        assert_eq!(ZKP::try_from(&tampered[..]).err(), Some(Error::InvalidPoint));
+
+        assert_eq!(
+            decode_g1(&G1Affine::identity().to_uncompressed()),
+            Ok(G1Affine::identity())
+        );
+        assert_eq!(
+            decode_scalar([0xff; 32]).err(),
+            Some(Error::InvalidPublicInput)
+        );
+    }
+}
@@ -34,6 +34,21 @@ macro_rules! bounded_int {
                 }
             }
 
+            impl<'de, const MIN: $ty, const MAX: $ty> serde::Deserialize<'de> for $Struct<MIN, MAX> {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let n = <$ty as serde::Deserialize>::deserialize(deserializer)?;
+                    Self::new(n).map_err(|err| {
+                        serde::de::Error::custom(format_args!(
+                            "{} is not within [{MIN}, {MAX}]",
+                            err.found
+                        ))
+                    })
+                }
+            }
+
             impl<const MIN: $ty, const MAX: $ty> TryFrom<$ty> for $Struct<MIN, MAX> {
                 type Error = BoundedIntError<$ty>;
 
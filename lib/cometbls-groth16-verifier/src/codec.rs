@@ -0,0 +1,608 @@
+//! Round-trippable encodings of proofs, keys and headers: raw bytes, hex, and serde (JSON) with
+//! hex encoded points and hashes.
+//!
+//! Points are big endian and uncompressed in serde formats, whatever the byte order of the
+//! [`ZKP`] they belong to; compressed points are accepted too.
+
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+
+use byteorder::ByteOrder;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use substrate_bn::{AffineG1, AffineG2, Fr};
+
+use crate::{
+    compressed::{G1_COMPRESSED_SIZE, G2_COMPRESSED_SIZE},
+    ByteArrayExt, CommitmentHash, CommitmentKey, Error, G1Affine, G1AffineBE, G2Affine, G2AffineBE,
+    LightHeader, Proof, Timestamp, VerifyingKey, COMPRESSED_PROOF_SIZE, ZKP, G1_SIZE, G2_SIZE,
+};
+
+/// A canonical byte encoding, and the hex encoding derived from it.
+pub trait Codec: Sized {
+    fn encode(&self) -> Vec<u8>;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error>;
+
+    fn encode_hex(&self) -> String {
+        hex::encode(self.encode())
+    }
+
+    /// Decodes hex, with or without a `0x` prefix.
+    fn decode_hex(s: &str) -> Result<Self, Error> {
+        let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|_| Error::InvalidHex)?;
+        Self::decode(&bytes)
+    }
+}
+
+const TIMESTAMP_SIZE: usize = 8 + 4;
+const LIGHT_HEADER_SIZE: usize = 8 + TIMESTAMP_SIZE + 3 * 32;
+const PROOF_SIZE: usize = G1_SIZE + G2_SIZE + G1_SIZE;
+
+/// `seconds ‖ nanos`, big endian.
+impl Codec for Timestamp {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = i64::from(self.seconds).to_be_bytes().to_vec();
+        bytes.extend(i32::from(self.nanos).to_be_bytes());
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes =
+            <[u8; TIMESTAMP_SIZE]>::try_from(bytes).map_err(|_| Error::InvalidSliceLength)?;
+        Ok(Timestamp {
+            seconds: i64::from_be_bytes(bytes.array_slice::<0, 8>())
+                .try_into()
+                .map_err(|_| Error::InvalidTimestamp)?,
+            nanos: i32::from_be_bytes(bytes.array_slice::<8, 4>())
+                .try_into()
+                .map_err(|_| Error::InvalidTimestamp)?,
+        })
+    }
+}
+
+/// `height ‖ time ‖ validators_hash ‖ next_validators_hash ‖ app_hash`, big endian.
+impl Codec for LightHeader {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = i64::from(self.height).to_be_bytes().to_vec();
+        bytes.extend(self.time.encode());
+        bytes.extend(self.validators_hash);
+        bytes.extend(self.next_validators_hash);
+        bytes.extend(self.app_hash);
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes =
+            <[u8; LIGHT_HEADER_SIZE]>::try_from(bytes).map_err(|_| Error::InvalidSliceLength)?;
+        Ok(LightHeader {
+            height: i64::from_be_bytes(bytes.array_slice::<0, 8>())
+                .try_into()
+                .map_err(|_| Error::InvalidHeight)?,
+            time: Timestamp::decode(&bytes.array_slice::<8, TIMESTAMP_SIZE>())?,
+            validators_hash: bytes.array_slice::<{ 8 + TIMESTAMP_SIZE }, 32>(),
+            next_validators_hash: bytes.array_slice::<{ 8 + TIMESTAMP_SIZE + 32 }, 32>(),
+            app_hash: bytes.array_slice::<{ 8 + TIMESTAMP_SIZE + 64 }, 32>(),
+        })
+    }
+}
+
+/// `a ‖ b ‖ c`, big endian and uncompressed, the layout of RISC Zero's seals.
+impl Codec for Proof {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = g1_bytes(self.a).to_vec();
+        bytes.extend(g2_bytes(self.b));
+        bytes.extend(g1_bytes(self.c));
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = <[u8; PROOF_SIZE]>::try_from(bytes).map_err(|_| Error::InvalidRawProof)?;
+        Ok(Proof {
+            a: G1AffineBE::try_from(bytes.array_slice::<0, G1_SIZE>())?.1,
+            b: G2AffineBE::try_from(bytes.array_slice::<G1_SIZE, G2_SIZE>())?.1,
+            c: G1AffineBE::try_from(bytes.array_slice::<{ G1_SIZE + G2_SIZE }, G1_SIZE>())?.1,
+        })
+    }
+}
+
+/// `a ‖ b ‖ c ‖ proof_commitments ‖ proof_commitment_pok` in the byte order of the ZKP,
+/// [`crate::RawZKP`] for CometBLS proofs. Decoding also accepts any number of commitments, see
+/// [`ZKP::from_gnark`], and compressed CometBLS proofs, see [`ZKP::try_from`].
+impl<FromOrder: ByteOrder> Codec for ZKP<FromOrder>
+where
+    G1Affine<FromOrder>: TryFrom<[u8; G1_SIZE], Error = Error>
+        + TryFrom<[u8; G1_COMPRESSED_SIZE], Error = Error>,
+    G2Affine<FromOrder>: TryFrom<[u8; G2_SIZE], Error = Error>
+        + TryFrom<[u8; G2_COMPRESSED_SIZE], Error = Error>,
+    [u8; G1_SIZE]: From<G1Affine<FromOrder>>,
+    [u8; G2_SIZE]: From<G2Affine<FromOrder>>,
+{
+    fn encode(&self) -> Vec<u8> {
+        let g1 = |point: AffineG1| <[u8; G1_SIZE]>::from(G1Affine::<FromOrder>::from(point));
+        let mut bytes = g1(self.proof.a).to_vec();
+        bytes.extend(<[u8; G2_SIZE]>::from(G2Affine::<FromOrder>::from(self.proof.b)));
+        bytes.extend(g1(self.proof.c));
+        for point in self.proof_commitments.iter().chain(&self.proof_commitment_pok) {
+            bytes.extend(g1(*point));
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() == COMPRESSED_PROOF_SIZE {
+            ZKP::try_from(bytes)
+        } else {
+            ZKP::from_gnark(bytes)
+        }
+    }
+}
+
+/// `alpha_g1 ‖ beta_g2 ‖ gamma_g2 ‖ delta_g2 ‖ gamma_abc_g1 ‖ commitment_key ‖
+/// public_and_commitment_committed ‖ commitment_hash`.
+///
+/// Points are big endian and uncompressed. Slices are prefixed with their length as a big endian
+/// `u32` and indices are `u64`, like gnark's raw encoding. The commitment key is prefixed with
+/// `1`, or is a single `0` if there is none, and the hash is `0` for gnark's and `1` for
+/// CometBLS'.
+impl Codec for VerifyingKey {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = g1_bytes(self.alpha_g1).to_vec();
+        for point in [self.beta_g2, self.gamma_g2, self.delta_g2] {
+            bytes.extend(g2_bytes(point));
+        }
+        bytes.extend(len_bytes(self.gamma_abc_g1.len()));
+        for point in &self.gamma_abc_g1 {
+            bytes.extend(g1_bytes(*point));
+        }
+        match self.commitment_key {
+            Some(key) => {
+                bytes.push(1);
+                bytes.extend(g2_bytes(key.g));
+                bytes.extend(g2_bytes(key.g_root_sigma_neg));
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend(len_bytes(self.public_and_commitment_committed.len()));
+        for committed in &self.public_and_commitment_committed {
+            bytes.extend(len_bytes(committed.len()));
+            for index in committed {
+                bytes.extend((*index as u64).to_be_bytes());
+            }
+        }
+        bytes.push(match self.commitment_hash {
+            CommitmentHash::Gnark => 0,
+            CommitmentHash::CometBls => 1,
+        });
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        read_verifying_key(&mut reader)
+            .and_then(|vk| reader.finish(vk))
+            .map_err(|err| match err {
+                Error::InvalidRawProof => Error::InvalidVerifyingKey,
+                err => err,
+            })
+    }
+}
+
+fn read_verifying_key(reader: &mut Reader) -> Result<VerifyingKey, Error> {
+    Ok(VerifyingKey {
+        alpha_g1: reader.g1()?,
+        beta_g2: reader.g2()?,
+        gamma_g2: reader.g2()?,
+        delta_g2: reader.g2()?,
+        gamma_abc_g1: reader.vec(Reader::g1)?,
+        commitment_key: match reader.u8()? {
+            0 => None,
+            1 => Some(CommitmentKey {
+                g: reader.g2()?,
+                g_root_sigma_neg: reader.g2()?,
+            }),
+            _ => return Err(Error::InvalidVerifyingKey),
+        },
+        public_and_commitment_committed: reader.vec(|reader| reader.vec(read_index))?,
+        commitment_hash: match reader.u8()? {
+            0 => CommitmentHash::Gnark,
+            1 => CommitmentHash::CometBls,
+            _ => return Err(Error::InvalidVerifyingKey),
+        },
+    })
+}
+
+fn read_index(reader: &mut Reader) -> Result<usize, Error> {
+    usize::try_from(reader.u64()?).map_err(|_| Error::InvalidVerifyingKey)
+}
+
+fn len_bytes(len: usize) -> [u8; 4] {
+    u32::try_from(len)
+        .expect("slices are shorter than 2^32; qed;")
+        .to_be_bytes()
+}
+
+fn g1_bytes(point: AffineG1) -> [u8; G1_SIZE] {
+    G1AffineBE::from(point).into()
+}
+
+fn g2_bytes(point: AffineG2) -> [u8; G2_SIZE] {
+    G2AffineBE::from(point).into()
+}
+
+/// A point as the hex of its big endian encoding.
+struct G1Hex(AffineG1);
+
+impl Serialize for G1Hex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(g1_bytes(self.0)))
+    }
+}
+
+impl<'de> Deserialize<'de> for G1Hex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = hex_bytes(deserializer)?;
+        let point = match <[u8; G1_COMPRESSED_SIZE]>::try_from(&bytes[..]) {
+            Ok(compressed) => G1AffineBE::try_from(compressed),
+            Err(_) => <[u8; G1_SIZE]>::try_from(&bytes[..])
+                .map_err(|_| Error::InvalidSliceLength)
+                .and_then(G1AffineBE::try_from),
+        };
+        point.map(|point| G1Hex(point.1)).map_err(invalid)
+    }
+}
+
+/// [`G1Hex`] in `G2`.
+struct G2Hex(AffineG2);
+
+impl Serialize for G2Hex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(g2_bytes(self.0)))
+    }
+}
+
+impl<'de> Deserialize<'de> for G2Hex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = hex_bytes(deserializer)?;
+        let point = match <[u8; G2_COMPRESSED_SIZE]>::try_from(&bytes[..]) {
+            Ok(compressed) => G2AffineBE::try_from(compressed),
+            Err(_) => <[u8; G2_SIZE]>::try_from(&bytes[..])
+                .map_err(|_| Error::InvalidSliceLength)
+                .and_then(G2AffineBE::try_from),
+        };
+        point.map(|point| G2Hex(point.1)).map_err(invalid)
+    }
+}
+
+fn hex_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    hex::decode(s.strip_prefix("0x").unwrap_or(&s)).map_err(D::Error::custom)
+}
+
+fn invalid<E: serde::de::Error>(err: Error) -> E {
+    E::custom(format_args!("{err:?}"))
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProofRepr {
+    a: G1Hex,
+    b: G2Hex,
+    c: G1Hex,
+}
+
+impl From<&Proof> for ProofRepr {
+    fn from(proof: &Proof) -> Self {
+        ProofRepr {
+            a: G1Hex(proof.a),
+            b: G2Hex(proof.b),
+            c: G1Hex(proof.c),
+        }
+    }
+}
+
+impl From<ProofRepr> for Proof {
+    fn from(ProofRepr { a, b, c }: ProofRepr) -> Self {
+        Proof {
+            a: a.0,
+            b: b.0,
+            c: c.0,
+        }
+    }
+}
+
+impl Serialize for Proof {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProofRepr::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Proof {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ProofRepr::deserialize(deserializer).map(Proof::from)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ZkpRepr {
+    proof: ProofRepr,
+    proof_commitments: Vec<G1Hex>,
+    proof_commitment_pok: Option<G1Hex>,
+}
+
+impl<FromOrder> Serialize for ZKP<FromOrder> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ZkpRepr {
+            proof: ProofRepr::from(&self.proof),
+            proof_commitments: self.proof_commitments.iter().copied().map(G1Hex).collect(),
+            proof_commitment_pok: self.proof_commitment_pok.map(G1Hex),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, FromOrder> Deserialize<'de> for ZKP<FromOrder> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ZkpRepr::deserialize(deserializer)?;
+        // The encoding tells commitments apart from their proof of knowledge by the length.
+        if repr.proof_commitments.is_empty() != repr.proof_commitment_pok.is_none() {
+            return Err(invalid(Error::InvalidCommitment));
+        }
+        Ok(ZKP {
+            proof: repr.proof.into(),
+            proof_commitments: repr.proof_commitments.into_iter().map(|point| point.0).collect(),
+            proof_commitment_pok: repr.proof_commitment_pok.map(|point| point.0),
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommitmentKeyRepr {
+    g: G2Hex,
+    g_root_sigma_neg: G2Hex,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VerifyingKeyRepr {
+    alpha_g1: G1Hex,
+    beta_g2: G2Hex,
+    gamma_g2: G2Hex,
+    delta_g2: G2Hex,
+    gamma_abc_g1: Vec<G1Hex>,
+    commitment_key: Option<CommitmentKeyRepr>,
+    public_and_commitment_committed: Vec<Vec<usize>>,
+    commitment_hash: CommitmentHash,
+}
+
+impl Serialize for VerifyingKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        VerifyingKeyRepr {
+            alpha_g1: G1Hex(self.alpha_g1),
+            beta_g2: G2Hex(self.beta_g2),
+            gamma_g2: G2Hex(self.gamma_g2),
+            delta_g2: G2Hex(self.delta_g2),
+            gamma_abc_g1: self.gamma_abc_g1.iter().copied().map(G1Hex).collect(),
+            commitment_key: self.commitment_key.map(|key| CommitmentKeyRepr {
+                g: G2Hex(key.g),
+                g_root_sigma_neg: G2Hex(key.g_root_sigma_neg),
+            }),
+            public_and_commitment_committed: self.public_and_commitment_committed.clone(),
+            commitment_hash: self.commitment_hash,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VerifyingKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = VerifyingKeyRepr::deserialize(deserializer)?;
+        Ok(VerifyingKey {
+            alpha_g1: repr.alpha_g1.0,
+            beta_g2: repr.beta_g2.0,
+            gamma_g2: repr.gamma_g2.0,
+            delta_g2: repr.delta_g2.0,
+            gamma_abc_g1: repr.gamma_abc_g1.into_iter().map(|point| point.0).collect(),
+            commitment_key: repr.commitment_key.map(|key| CommitmentKey {
+                g: key.g.0,
+                g_root_sigma_neg: key.g_root_sigma_neg.0,
+            }),
+            public_and_commitment_committed: repr.public_and_commitment_committed,
+            commitment_hash: repr.commitment_hash,
+        })
+    }
+}
+
+/// Reads gnark's raw (uncompressed) encoding, as written by `WriteRawTo`. Slices are prefixed with
+/// their length as a big endian `u32`.
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        if self.0.len() < N {
+            return Err(Error::InvalidRawProof);
+        }
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        Ok(bytes.try_into().expect("split at N; qed;"))
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.take()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_be_bytes(self.take()?))
+    }
+
+    pub(crate) fn fr(&mut self) -> Result<Fr, Error> {
+        Fr::from_slice(&self.take::<32>()?).map_err(|_| Error::InvalidRawProof)
+    }
+
+    pub(crate) fn g1(&mut self) -> Result<AffineG1, Error> {
+        Ok(G1AffineBE::try_from(self.take::<G1_SIZE>()?)?.1)
+    }
+
+    pub(crate) fn g2(&mut self) -> Result<AffineG2, Error> {
+        Ok(G2AffineBE::try_from(self.take::<G2_SIZE>()?)?.1)
+    }
+
+    pub(crate) fn vec<T>(
+        &mut self,
+        read: impl Fn(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let len = self.u32()? as usize;
+        // Every element is at least 4 bytes, do not allocate for lengths the input cannot hold.
+        if len > self.0.len() / 4 {
+            return Err(Error::InvalidRawProof);
+        }
+        (0..len).map(|_| read(self)).collect()
+    }
+
+    pub(crate) fn finish<T>(self, value: T) -> Result<T, Error> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            Err(Error::InvalidRawProof)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use byteorder::{BigEndian, LittleEndian};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{tests::fixture_zkp, EXPECTED_PROOF_SIZE};
+
+    fn header() -> LightHeader {
+        LightHeader {
+            height: 969001.try_into().unwrap(),
+            time: Timestamp {
+                seconds: 1710783278.try_into().unwrap(),
+                nanos: 499600406.try_into().unwrap(),
+            },
+            validators_hash: [1; 32],
+            next_validators_hash: [2; 32],
+            app_hash: [3; 32],
+        }
+    }
+
+    fn verifying_key(zkp: &ZKP<BigEndian>) -> VerifyingKey {
+        VerifyingKey {
+            alpha_g1: zkp.proof.a,
+            beta_g2: zkp.proof.b,
+            gamma_g2: zkp.proof.b,
+            delta_g2: zkp.proof.b,
+            gamma_abc_g1: vec![zkp.proof.a, zkp.proof.c],
+            commitment_key: Some(CommitmentKey {
+                g: zkp.proof.b,
+                g_root_sigma_neg: zkp.proof.b,
+            }),
+            public_and_commitment_committed: vec![vec![], vec![0, 2]],
+            commitment_hash: CommitmentHash::Gnark,
+        }
+    }
+
+    /// Decodes `value` from its raw, hex and JSON encodings, and checks every one encodes to
+    /// the same bytes.
+    fn assert_round_trip<T>(value: &T)
+    where
+        T: Codec + Serialize + for<'de> Deserialize<'de>,
+    {
+        let bytes = value.encode();
+        assert_eq!(T::decode(&bytes).unwrap().encode(), bytes);
+        assert_eq!(T::decode_hex(&value.encode_hex()).unwrap().encode(), bytes);
+        assert_eq!(
+            T::decode_hex(&format!("0x{}", value.encode_hex())).unwrap().encode(),
+            bytes
+        );
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap().encode(), bytes);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let zkp = ZKP::<BigEndian>::try_from(&fixture_zkp()[..]).unwrap();
+        assert_round_trip(&header());
+        assert_round_trip(&header().time);
+        assert_round_trip(&Proof::decode(&zkp.proof.encode()).unwrap());
+        assert_round_trip(&zkp);
+
+        let little_endian = ZKP::<LittleEndian> {
+            proof: Proof::decode(&zkp.proof.encode()).unwrap(),
+            proof_commitments: zkp.proof_commitments.clone(),
+            proof_commitment_pok: zkp.proof_commitment_pok,
+            _marker: PhantomData,
+        };
+        assert_round_trip(&little_endian);
+        assert_ne!(little_endian.encode(), zkp.encode());
+        assert_eq!(
+            serde_json::to_string(&little_endian).unwrap(),
+            serde_json::to_string(&zkp).unwrap()
+        );
+
+        assert_round_trip(&verifying_key(&zkp));
+
+        let mut vk = verifying_key(&zkp);
+        vk.commitment_key = None;
+        vk.public_and_commitment_committed = vec![];
+        vk.commitment_hash = CommitmentHash::CometBls;
+        assert_round_trip(&vk);
+    }
+
+    #[test]
+    fn test_zkp() {
+        let zkp = ZKP::<BigEndian>::try_from(&fixture_zkp()[..]).unwrap();
+        assert_eq!(zkp.encode().len(), EXPECTED_PROOF_SIZE);
+
+        // Structural edits survive the round trip.
+        let mut json: serde_json::Value = serde_json::to_value(&zkp).unwrap();
+        let commitments = json!([json["proof"]["a"], json["proof"]["c"]]);
+        json["proof_commitments"] = commitments;
+        let edited: ZKP<BigEndian> = serde_json::from_value(json.clone()).unwrap();
+        assert!(edited.proof_commitments == vec![zkp.proof.a, zkp.proof.c]);
+        let decoded = ZKP::<BigEndian>::decode(&edited.encode()).unwrap();
+        assert!(decoded.proof_commitments == edited.proof_commitments);
+
+        // Commitments need their proof of knowledge, and the proof of knowledge commitments.
+        let mut without_pok = json.clone();
+        without_pok["proof_commitment_pok"] = serde_json::Value::Null;
+        assert!(serde_json::from_value::<ZKP<BigEndian>>(without_pok).is_err());
+        let mut without_commitments = json;
+        without_commitments["proof_commitments"] = json!([]);
+        assert!(serde_json::from_value::<ZKP<BigEndian>>(without_commitments).is_err());
+
+        let json = serde_json::to_value(&zkp.proof).unwrap();
+        let a = hex::decode(json["a"].as_str().unwrap()).unwrap();
+        assert_eq!(a, g1_bytes(zkp.proof.a));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let zkp = ZKP::<BigEndian>::try_from(&fixture_zkp()[..]).unwrap();
+        assert_eq!(ZKP::<BigEndian>::decode_hex("zz").err(), Some(Error::InvalidHex));
+        assert_eq!(
+            LightHeader::decode(&header().encode()[1..]).err(),
+            Some(Error::InvalidSliceLength)
+        );
+        let mut timestamp = header().time.encode();
+        timestamp[8] = 0xff;
+        assert_eq!(Timestamp::decode(&timestamp).err(), Some(Error::InvalidTimestamp));
+
+        let bytes = verifying_key(&zkp).encode();
+        assert_eq!(
+            VerifyingKey::decode(&bytes[..bytes.len() - 1]).err(),
+            Some(Error::InvalidVerifyingKey)
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(VerifyingKey::decode(&trailing).err(), Some(Error::InvalidVerifyingKey));
+    }
+}
@@ -0,0 +1,193 @@
+//! gnark's compressed BN254 points: the `x` coordinate, with the top two bits of the first byte
+//! flagging which of the two `y` is meant.
+
+use core::marker::PhantomData;
+
+use hex_literal::hex;
+use substrate_bn::{AffineG1, AffineG2, Fq, Fq2};
+
+use crate::{
+    curve::{Bn254, Curve},
+    ByteArrayExt, Error, G1Affine, G1AffineBE, G2Affine, G2AffineBE, RawCompressedZKP, RawZKP,
+    COMPRESSED_PROOF_SIZE, FQ_SIZE, G1_SIZE, G2_SIZE,
+};
+
+pub const G1_COMPRESSED_SIZE: usize = Bn254::G1_COMPRESSED_SIZE;
+pub const G2_COMPRESSED_SIZE: usize = Bn254::G2_COMPRESSED_SIZE;
+
+const FLAG_MASK: u8 = 0b11 << 6;
+const FLAG_SMALLEST: u8 = 0b10 << 6;
+const FLAG_LARGEST: u8 = 0b11 << 6;
+
+/// `(q - 1) / 2`, an `Fq` is lexicographically largest if it is above.
+const HALF_Q: [u8; FQ_SIZE] =
+    hex!("183227397098d014dc2822db40c0ac2ecbc0b548b438e5469e10460b6c3e7ea3");
+
+/// `b` of the twist, `3 / (9 + u)`.
+fn twist_b() -> Fq2 {
+    Fq2::new(
+        Fq::from_slice(&hex!(
+            "2b149d40ceb8aaae81be18991be06ac3b5b4c5e559dbefa33267e6dc24a138e5"
+        ))
+        .expect("b is in Fq; qed;"),
+        Fq::from_slice(&hex!(
+            "009713b03af0fed4cd2cafadeed8fdf4a74fa084e52d1852e4a2bd0685c315d2"
+        ))
+        .expect("b is in Fq; qed;"),
+    )
+}
+
+impl TryFrom<[u8; G1_COMPRESSED_SIZE]> for G1AffineBE {
+    type Error = Error;
+    fn try_from(mut value: [u8; G1_COMPRESSED_SIZE]) -> Result<Self, Self::Error> {
+        let largest = take_flag(&mut value)?;
+        Ok(G1Affine(PhantomData, g1_from_x(decode_fq(value)?, largest)?))
+    }
+}
+
+impl TryFrom<[u8; G2_COMPRESSED_SIZE]> for G2AffineBE {
+    type Error = Error;
+    fn try_from(mut value: [u8; G2_COMPRESSED_SIZE]) -> Result<Self, Self::Error> {
+        let largest = take_flag(&mut value)?;
+        let x = Fq2::new(
+            decode_fq(value.array_slice::<FQ_SIZE, FQ_SIZE>())?,
+            decode_fq(value.array_slice::<0, FQ_SIZE>())?,
+        );
+        Ok(G2Affine(PhantomData, g2_from_x(x, largest)?))
+    }
+}
+
+impl G1AffineBE {
+    pub fn to_compressed(&self) -> [u8; G1_COMPRESSED_SIZE] {
+        let mut bytes = fq_bytes(self.1.x());
+        bytes[0] |= flag(fq_is_largest(self.1.y()));
+        bytes
+    }
+}
+
+impl G2AffineBE {
+    pub fn to_compressed(&self) -> [u8; G2_COMPRESSED_SIZE] {
+        let mut bytes = [0; G2_COMPRESSED_SIZE];
+        bytes[..FQ_SIZE].copy_from_slice(&fq_bytes(self.1.x().imaginary()));
+        bytes[FQ_SIZE..].copy_from_slice(&fq_bytes(self.1.x().real()));
+        bytes[0] |= flag(fq2_is_largest(self.1.y()));
+        bytes
+    }
+}
+
+/// Compresses every point of an uncompressed proof, halving its size.
+pub fn compress_zkp(raw: &RawZKP) -> Result<RawCompressedZKP, Error> {
+    const C1: usize = G1_COMPRESSED_SIZE;
+    const C2: usize = G2_COMPRESSED_SIZE;
+    let g1 = |bytes: [u8; G1_SIZE]| G1AffineBE::try_from(bytes).map(|g1| g1.to_compressed());
+
+    let mut compressed = [0; COMPRESSED_PROOF_SIZE];
+    compressed[..C1].copy_from_slice(&g1(raw.array_slice::<0, G1_SIZE>())?);
+    compressed[C1..C1 + C2].copy_from_slice(
+        &G2AffineBE::try_from(raw.array_slice::<G1_SIZE, G2_SIZE>())?.to_compressed(),
+    );
+    for (i, point) in compressed[C1 + C2..].chunks_exact_mut(C1).enumerate() {
+        let offset = G1_SIZE + G2_SIZE + i * G1_SIZE;
+        let bytes = raw[offset..offset + G1_SIZE].try_into().expect("within the proof; qed;");
+        point.copy_from_slice(&g1(bytes)?);
+    }
+    Ok(compressed)
+}
+
+/// The point with `x` and the larger or smaller of the two `y`.
+pub(crate) fn g1_from_x(x: Fq, largest: bool) -> Result<AffineG1, Error> {
+    let y = (x * x * x + Fq::from_str("3").expect("3 is in Fq; qed;"))
+        .sqrt()
+        .ok_or(Error::InvalidPoint)?;
+    let y = if fq_is_largest(y) == largest { y } else { -y };
+    AffineG1::new(x, y).map_err(|_| Error::InvalidPoint)
+}
+
+/// [`g1_from_x`] on the twist.
+pub(crate) fn g2_from_x(x: Fq2, largest: bool) -> Result<AffineG2, Error> {
+    let y = (x * x * x + twist_b()).sqrt().ok_or(Error::InvalidPoint)?;
+    let y = if fq2_is_largest(y) == largest { y } else { -y };
+    AffineG2::new(x, y).map_err(|_| Error::InvalidPoint)
+}
+
+/// Clears the flag of a compressed point, returning whether `y` is the largest one. Points at
+/// infinity are rejected like uncompressed ones.
+fn take_flag(value: &mut [u8]) -> Result<bool, Error> {
+    let flag = value[0] & FLAG_MASK;
+    value[0] &= !FLAG_MASK;
+    match flag {
+        FLAG_SMALLEST => Ok(false),
+        FLAG_LARGEST => Ok(true),
+        _ => Err(Error::InvalidPoint),
+    }
+}
+
+fn flag(largest: bool) -> u8 {
+    if largest {
+        FLAG_LARGEST
+    } else {
+        FLAG_SMALLEST
+    }
+}
+
+fn decode_fq(bytes: [u8; FQ_SIZE]) -> Result<Fq, Error> {
+    Fq::from_slice(&bytes).map_err(|_| Error::InvalidPoint)
+}
+
+pub(crate) fn fq_bytes(fq: Fq) -> [u8; FQ_SIZE] {
+    let mut bytes = [0; FQ_SIZE];
+    fq.to_big_endian(&mut bytes).expect("an Fq is 32 bytes; qed;");
+    bytes
+}
+
+pub(crate) fn fq_is_largest(fq: Fq) -> bool {
+    fq_bytes(fq) > HALF_Q
+}
+
+/// gnark orders `Fq2` by the imaginary part, and by the real one if it is zero.
+pub(crate) fn fq2_is_largest(fq2: Fq2) -> bool {
+    if fq2.imaginary() == Fq::zero() {
+        fq_is_largest(fq2.real())
+    } else {
+        fq_is_largest(fq2.imaginary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::BigEndian;
+
+    use super::*;
+    use crate::{tests::fixture_zkp, ZKP};
+
+    #[test]
+    fn test_compressed_zkp() {
+        let raw = fixture_zkp();
+        let uncompressed = ZKP::<BigEndian>::try_from(&raw[..]).unwrap();
+        let compressed = ZKP::<BigEndian>::try_from(&compress_zkp(&raw).unwrap()[..]).unwrap();
+
+        assert!(compressed.proof.a == uncompressed.proof.a);
+        assert!(compressed.proof.b == uncompressed.proof.b);
+        assert!(compressed.proof.c == uncompressed.proof.c);
+        assert!(compressed.proof_commitments == uncompressed.proof_commitments);
+        assert!(compressed.proof_commitment_pok == uncompressed.proof_commitment_pok);
+    }
+
+    #[test]
+    fn test_flags() {
+        let raw = fixture_zkp();
+        let a = G1AffineBE::try_from(raw.array_slice::<0, G1_SIZE>()).unwrap();
+        let mut compressed = a.to_compressed();
+
+        // The other root is the negated point.
+        compressed[0] ^= FLAG_SMALLEST ^ FLAG_LARGEST;
+        let negated = G1AffineBE::try_from(compressed).unwrap();
+        assert!(negated.1.x() == a.1.x());
+        assert!(negated.1.y() == -a.1.y());
+
+        compressed[0] &= !FLAG_MASK;
+        assert_eq!(G1AffineBE::try_from(compressed).err(), Some(Error::InvalidPoint));
+        compressed[0] |= 0b01 << 6;
+        assert_eq!(G1AffineBE::try_from(compressed).err(), Some(Error::InvalidPoint));
+    }
+}
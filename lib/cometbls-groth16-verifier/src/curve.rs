@@ -0,0 +1,194 @@
+//! The sizes of gnark's uncompressed encodings, and the arithmetic [`crate::groth16`] verifies
+//! proofs with, per curve.
+
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::Error;
+
+/// A pairing friendly curve gnark proves over.
+pub trait Curve {
+    /// A base field element.
+    const FQ_SIZE: usize;
+    /// An uncompressed `G1` point, `x ‖ y`.
+    const G1_SIZE: usize = 2 * Self::FQ_SIZE;
+    /// An uncompressed `G2` point, `x.c1 ‖ x.c0 ‖ y.c1 ‖ y.c0`.
+    const G2_SIZE: usize = 2 * Self::G1_SIZE;
+    /// A Groth16 proof with a single commitment: `a ‖ b ‖ c ‖ commitment ‖ commitment_pok`.
+    const PROOF_SIZE: usize = Self::G1_SIZE + Self::G2_SIZE + 3 * Self::G1_SIZE;
+    /// A compressed `G1` point, `x` with the flags in its top bits.
+    const G1_COMPRESSED_SIZE: usize = Self::FQ_SIZE;
+    /// A compressed `G2` point, `x.c1 ‖ x.c0` with the flags in the top bits of `x.c1`.
+    const G2_COMPRESSED_SIZE: usize = 2 * Self::FQ_SIZE;
+    /// [`Curve::PROOF_SIZE`] with compressed points.
+    const COMPRESSED_PROOF_SIZE: usize = Self::PROOF_SIZE / 2;
+
+    /// The size of a Groth16 proof with `commitments` commitments, followed by their proof of
+    /// knowledge if there are any.
+    fn proof_size(commitments: usize) -> usize {
+        let pok = usize::from(commitments > 0);
+        Self::G1_SIZE + Self::G2_SIZE + (1 + commitments + pok) * Self::G1_SIZE
+    }
+
+    /// The number of commitments of a Groth16 proof of `len` bytes, the inverse of
+    /// [`Curve::proof_size`].
+    fn proof_commitments(len: usize) -> Option<usize> {
+        let points = len.checked_sub(Self::G2_SIZE)?;
+        if points % Self::G1_SIZE != 0 {
+            return None;
+        }
+        match points / Self::G1_SIZE {
+            2 => Some(0),
+            // A single commitment needs a proof of knowledge.
+            0 | 1 | 3 => None,
+            points => Some(points - 3),
+        }
+    }
+}
+
+/// The groups, scalars and pairing of a [`Curve`], as the Groth16 verifier needs them.
+pub trait Pairing: Curve {
+    type Scalar: Copy
+        + Add<Output = Self::Scalar>
+        + Sub<Output = Self::Scalar>
+        + Mul<Output = Self::Scalar>
+        + Neg<Output = Self::Scalar>;
+    /// An affine `G1` point, as keys and proofs hold them.
+    type G1: Copy;
+    /// `G1` in the representation sums are computed in.
+    type G1Sum: Copy
+        + From<Self::G1>
+        + Add<Output = Self::G1Sum>
+        + Mul<Self::Scalar, Output = Self::G1Sum>
+        + Neg<Output = Self::G1Sum>;
+    /// An affine `G2` point.
+    type G2: Copy;
+
+    /// Whether the product of the pairings of `terms` is one.
+    fn pairing_product_is_one(terms: &[(Self::G1Sum, Self::G2)]) -> bool;
+
+    /// gnark's `fr.Hash(message, dst, 1)` over the scalar field.
+    fn hash_to_scalar(message: &[u8], dst: &[u8]) -> Self::Scalar;
+
+    /// A big endian scalar, `None` unless it is reduced.
+    fn scalar_from_bytes(bytes: [u8; 32]) -> Option<Self::Scalar>;
+
+    /// The big endian scalar.
+    fn scalar_bytes(scalar: &Self::Scalar) -> [u8; 32];
+
+    /// The uncompressed point, as gnark hashes commitments.
+    fn g1_bytes(point: &Self::G1) -> Result<Vec<u8>, Error>;
+}
+
+/// BN254, the curve of CometBLS and of the RISC Zero Groth16 wrapper.
+#[derive(Clone, Copy)]
+pub struct Bn254;
+
+impl Curve for Bn254 {
+    const FQ_SIZE: usize = 32;
+}
+
+impl Pairing for Bn254 {
+    type Scalar = substrate_bn::Fr;
+    type G1 = substrate_bn::AffineG1;
+    type G1Sum = substrate_bn::G1;
+    type G2 = substrate_bn::AffineG2;
+
+    fn pairing_product_is_one(terms: &[(Self::G1Sum, Self::G2)]) -> bool {
+        let terms: Vec<_> = terms.iter().map(|(g1, g2)| (*g1, (*g2).into())).collect();
+        substrate_bn::pairing_batch(&terms) == substrate_bn::Gt::one()
+    }
+
+    fn hash_to_scalar(message: &[u8], dst: &[u8]) -> Self::Scalar {
+        crate::groth16::hash_to_fr(message, dst)
+    }
+
+    fn scalar_from_bytes(bytes: [u8; 32]) -> Option<Self::Scalar> {
+        substrate_bn::Fr::from_slice(&bytes).ok()
+    }
+
+    fn scalar_bytes(scalar: &Self::Scalar) -> [u8; 32] {
+        crate::groth16::fr_bytes(scalar)
+    }
+
+    fn g1_bytes(point: &Self::G1) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; Self::G1_SIZE];
+        point
+            .x()
+            .to_big_endian(&mut bytes[..Self::FQ_SIZE])
+            .map_err(|_| Error::InvalidCommitment)?;
+        point
+            .y()
+            .to_big_endian(&mut bytes[Self::FQ_SIZE..])
+            .map_err(|_| Error::InvalidCommitment)?;
+        Ok(bytes)
+    }
+}
+
+/// BLS12-381.
+#[derive(Clone, Copy)]
+pub struct Bls12_381;
+
+impl Curve for Bls12_381 {
+    const FQ_SIZE: usize = 48;
+}
+
+impl Pairing for Bls12_381 {
+    type Scalar = bls12_381::Scalar;
+    type G1 = bls12_381::G1Affine;
+    type G1Sum = bls12_381::G1Projective;
+    type G2 = bls12_381::G2Affine;
+
+    fn pairing_product_is_one(terms: &[(Self::G1Sum, Self::G2)]) -> bool {
+        let prepared: Vec<_> = terms
+            .iter()
+            .map(|(g1, g2)| (bls12_381::G1Affine::from(g1), bls12_381::G2Prepared::from(*g2)))
+            .collect();
+        let terms: Vec<_> = prepared.iter().map(|(g1, g2)| (g1, g2)).collect();
+        bls12_381::multi_miller_loop(&terms).final_exponentiation() == bls12_381::Gt::identity()
+    }
+
+    fn hash_to_scalar(message: &[u8], dst: &[u8]) -> Self::Scalar {
+        crate::bls::hash_to_scalar(message, dst)
+    }
+
+    fn scalar_from_bytes(bytes: [u8; 32]) -> Option<Self::Scalar> {
+        crate::bls::decode_scalar(bytes).ok()
+    }
+
+    fn scalar_bytes(scalar: &Self::Scalar) -> [u8; 32] {
+        let mut bytes = scalar.to_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    fn g1_bytes(point: &Self::G1) -> Result<Vec<u8>, Error> {
+        Ok(point.to_uncompressed().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(Bn254::PROOF_SIZE, 384);
+        assert_eq!(Bn254::COMPRESSED_PROOF_SIZE, 192);
+        assert_eq!(Bls12_381::G1_SIZE, 96);
+        assert_eq!(Bls12_381::G2_SIZE, 192);
+        assert_eq!(Bls12_381::PROOF_SIZE, 576);
+        assert_eq!(Bls12_381::proof_size(1), Bls12_381::PROOF_SIZE);
+    }
+
+    #[test]
+    fn test_proof_commitments() {
+        for commitments in 0..4 {
+            let len = Bn254::proof_size(commitments);
+            assert_eq!(Bn254::proof_commitments(len), Some(commitments));
+            assert_eq!(Bn254::proof_commitments(len + 1), None);
+        }
+        assert_eq!(Bn254::proof_commitments(2 * Bn254::G1_SIZE + Bn254::G2_SIZE), None);
+        assert_eq!(Bls12_381::proof_commitments(Bls12_381::PROOF_SIZE), Some(1));
+    }
+}
@@ -0,0 +1,269 @@
+//! Little endian points, in arkworks' canonical serialization, and the encoding of points back
+//! into bytes.
+//!
+//! arkworks writes the coordinates little endian, the real part of an `Fq2` first, and flags the
+//! last byte: `0x80` if `y` is the larger of its two roots, `0x40` for the point at infinity. The
+//! compressed encoding is `x` alone, with the same flags.
+
+use core::marker::PhantomData;
+
+use byteorder::ByteOrder;
+use substrate_bn::{AffineG1, AffineG2, Fq, Fq2};
+
+use crate::{
+    compressed::{
+        fq2_is_largest, fq_bytes, fq_is_largest, g1_from_x, g2_from_x, G1_COMPRESSED_SIZE,
+        G2_COMPRESSED_SIZE,
+    },
+    ByteArrayExt, Error, G1Affine, G1AffineBE, G1AffineLE, G2Affine, G2AffineBE, G2AffineLE,
+    FQ_SIZE, G1_SIZE, G2_SIZE,
+};
+
+const FLAG_MASK: u8 = 0b11 << 6;
+const FLAG_LARGEST: u8 = 1 << 7;
+const FLAG_INFINITY: u8 = 1 << 6;
+
+impl<FromOrder: ByteOrder> From<AffineG1> for G1Affine<FromOrder> {
+    fn from(point: AffineG1) -> Self {
+        G1Affine(PhantomData, point)
+    }
+}
+
+impl<FromOrder> From<AffineG2> for G2Affine<FromOrder> {
+    fn from(point: AffineG2) -> Self {
+        G2Affine(PhantomData, point)
+    }
+}
+
+/// `x ‖ y`, the sign flag is ignored.
+impl TryFrom<[u8; G1_SIZE]> for G1AffineLE {
+    type Error = Error;
+    fn try_from(mut value: [u8; G1_SIZE]) -> Result<Self, Self::Error> {
+        take_flags(&mut value)?;
+        Ok(G1Affine(
+            PhantomData,
+            AffineG1::new(
+                decode_fq(value.array_slice::<0, FQ_SIZE>())?,
+                decode_fq(value.array_slice::<FQ_SIZE, FQ_SIZE>())?,
+            )
+            .map_err(|_| Error::InvalidPoint)?,
+        ))
+    }
+}
+
+/// `x.c0 ‖ x.c1 ‖ y.c0 ‖ y.c1`, the sign flag is ignored.
+impl TryFrom<[u8; G2_SIZE]> for G2AffineLE {
+    type Error = Error;
+    fn try_from(mut value: [u8; G2_SIZE]) -> Result<Self, Self::Error> {
+        take_flags(&mut value)?;
+        Ok(G2Affine(
+            PhantomData,
+            AffineG2::new(
+                decode_fq2(value.array_slice::<0, G1_SIZE>())?,
+                decode_fq2(value.array_slice::<G1_SIZE, G1_SIZE>())?,
+            )
+            .map_err(|_| Error::InvalidPoint)?,
+        ))
+    }
+}
+
+impl TryFrom<[u8; G1_COMPRESSED_SIZE]> for G1AffineLE {
+    type Error = Error;
+    fn try_from(mut value: [u8; G1_COMPRESSED_SIZE]) -> Result<Self, Self::Error> {
+        let largest = take_flags(&mut value)?;
+        Ok(G1Affine(PhantomData, g1_from_x(decode_fq(value)?, largest)?))
+    }
+}
+
+impl TryFrom<[u8; G2_COMPRESSED_SIZE]> for G2AffineLE {
+    type Error = Error;
+    fn try_from(mut value: [u8; G2_COMPRESSED_SIZE]) -> Result<Self, Self::Error> {
+        let largest = take_flags(&mut value)?;
+        Ok(G2Affine(PhantomData, g2_from_x(decode_fq2(value)?, largest)?))
+    }
+}
+
+impl From<G1AffineBE> for [u8; G1_SIZE] {
+    fn from(G1Affine(_, point): G1AffineBE) -> Self {
+        let mut bytes = [0; G1_SIZE];
+        bytes[..FQ_SIZE].copy_from_slice(&fq_bytes(point.x()));
+        bytes[FQ_SIZE..].copy_from_slice(&fq_bytes(point.y()));
+        bytes
+    }
+}
+
+impl From<G2AffineBE> for [u8; G2_SIZE] {
+    fn from(G2Affine(_, point): G2AffineBE) -> Self {
+        let mut bytes = [0; G2_SIZE];
+        for (chunk, fq) in bytes.chunks_exact_mut(FQ_SIZE).zip([
+            point.x().imaginary(),
+            point.x().real(),
+            point.y().imaginary(),
+            point.y().real(),
+        ]) {
+            chunk.copy_from_slice(&fq_bytes(fq));
+        }
+        bytes
+    }
+}
+
+impl From<G1AffineLE> for [u8; G1_SIZE] {
+    fn from(G1Affine(_, point): G1AffineLE) -> Self {
+        let mut bytes = [0; G1_SIZE];
+        bytes[..FQ_SIZE].copy_from_slice(&fq_le_bytes(point.x()));
+        bytes[FQ_SIZE..].copy_from_slice(&fq_le_bytes(point.y()));
+        bytes[G1_SIZE - 1] |= flag(fq_is_largest(point.y()));
+        bytes
+    }
+}
+
+impl From<G2AffineLE> for [u8; G2_SIZE] {
+    fn from(G2Affine(_, point): G2AffineLE) -> Self {
+        let mut bytes = [0; G2_SIZE];
+        bytes[..G1_SIZE].copy_from_slice(&fq2_le_bytes(point.x()));
+        bytes[G1_SIZE..].copy_from_slice(&fq2_le_bytes(point.y()));
+        bytes[G2_SIZE - 1] |= flag(fq2_is_largest(point.y()));
+        bytes
+    }
+}
+
+impl G1AffineLE {
+    pub fn to_compressed(&self) -> [u8; G1_COMPRESSED_SIZE] {
+        let mut bytes = fq_le_bytes(self.1.x());
+        bytes[G1_COMPRESSED_SIZE - 1] |= flag(fq_is_largest(self.1.y()));
+        bytes
+    }
+}
+
+impl G2AffineLE {
+    pub fn to_compressed(&self) -> [u8; G2_COMPRESSED_SIZE] {
+        let mut bytes = fq2_le_bytes(self.1.x());
+        bytes[G2_COMPRESSED_SIZE - 1] |= flag(fq2_is_largest(self.1.y()));
+        bytes
+    }
+}
+
+/// Clears the flags in the last byte, returning whether `y` is the largest root. Points at
+/// infinity are rejected like big endian ones.
+fn take_flags(value: &mut [u8]) -> Result<bool, Error> {
+    let last = value.last_mut().expect("points are not empty; qed;");
+    let flags = *last & FLAG_MASK;
+    *last &= !FLAG_MASK;
+    if flags & FLAG_INFINITY != 0 {
+        return Err(Error::InvalidPoint);
+    }
+    Ok(flags == FLAG_LARGEST)
+}
+
+fn flag(largest: bool) -> u8 {
+    if largest {
+        FLAG_LARGEST
+    } else {
+        0
+    }
+}
+
+fn decode_fq(mut bytes: [u8; FQ_SIZE]) -> Result<Fq, Error> {
+    bytes.reverse();
+    Fq::from_slice(&bytes).map_err(|_| Error::InvalidPoint)
+}
+
+fn decode_fq2(bytes: [u8; 2 * FQ_SIZE]) -> Result<Fq2, Error> {
+    Ok(Fq2::new(
+        decode_fq(bytes.array_slice::<0, FQ_SIZE>())?,
+        decode_fq(bytes.array_slice::<FQ_SIZE, FQ_SIZE>())?,
+    ))
+}
+
+fn fq_le_bytes(fq: Fq) -> [u8; FQ_SIZE] {
+    let mut bytes = fq_bytes(fq);
+    bytes.reverse();
+    bytes
+}
+
+fn fq2_le_bytes(fq2: Fq2) -> [u8; 2 * FQ_SIZE] {
+    let mut bytes = [0; 2 * FQ_SIZE];
+    bytes[..FQ_SIZE].copy_from_slice(&fq_le_bytes(fq2.real()));
+    bytes[FQ_SIZE..].copy_from_slice(&fq_le_bytes(fq2.imaginary()));
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, LittleEndian};
+    use substrate_bn::{Group, G1};
+
+    use super::*;
+    use crate::{tests::fixture_zkp, ZKP, EXPECTED_PROOF_SIZE};
+
+    fn g1_le(point: AffineG1) -> [u8; G1_SIZE] {
+        G1AffineLE::from(point).into()
+    }
+
+    fn g2_le(point: AffineG2) -> [u8; G2_SIZE] {
+        G2AffineLE::from(point).into()
+    }
+
+    #[test]
+    fn test_generator() {
+        // arkworks' `G1Affine::generator()`, `(1, 2)`, where `2` is the smaller root.
+        let generator = AffineG1::from_jacobian(G1::one()).unwrap();
+        let mut uncompressed = [0; G1_SIZE];
+        uncompressed[0] = 1;
+        uncompressed[FQ_SIZE] = 2;
+        let mut compressed = [0; G1_COMPRESSED_SIZE];
+        compressed[0] = 1;
+
+        assert_eq!(g1_le(generator), uncompressed);
+        assert_eq!(G1AffineLE::from(generator).to_compressed(), compressed);
+        assert!(G1AffineLE::try_from(uncompressed).unwrap().1 == generator);
+        assert!(G1AffineLE::try_from(compressed).unwrap().1 == generator);
+
+        compressed[G1_COMPRESSED_SIZE - 1] |= FLAG_LARGEST;
+        assert!(G1AffineLE::try_from(compressed).unwrap().1.y() == -generator.y());
+        compressed[G1_COMPRESSED_SIZE - 1] |= FLAG_INFINITY;
+        assert_eq!(G1AffineLE::try_from(compressed).err(), Some(Error::InvalidPoint));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let zkp = ZKP::<BigEndian>::try_from(&fixture_zkp()[..]).unwrap();
+        let (a, b) = (zkp.proof.a, zkp.proof.b);
+
+        let bytes: [u8; G1_SIZE] = G1AffineBE::from(a).into();
+        assert!(G1AffineBE::try_from(bytes).unwrap().1 == a);
+        let bytes: [u8; G2_SIZE] = G2AffineBE::from(b).into();
+        assert!(G2AffineBE::try_from(bytes).unwrap().1 == b);
+
+        assert!(G1AffineLE::try_from(g1_le(a)).unwrap().1 == a);
+        assert!(G2AffineLE::try_from(g2_le(b)).unwrap().1 == b);
+        let compressed = G1AffineLE::from(a).to_compressed();
+        assert!(G1AffineLE::try_from(compressed).unwrap().1 == a);
+        let compressed = G2AffineLE::from(b).to_compressed();
+        assert!(G2AffineLE::try_from(compressed).unwrap().1 == b);
+    }
+
+    #[test]
+    fn test_little_endian_zkp() {
+        let zkp = ZKP::<BigEndian>::try_from(&fixture_zkp()[..]).unwrap();
+        let g1s = [zkp.proof.c, zkp.proof_commitments[0], zkp.proof_commitment_pok.unwrap()];
+
+        let mut uncompressed = g1_le(zkp.proof.a).to_vec();
+        uncompressed.extend(g2_le(zkp.proof.b));
+        uncompressed.extend(g1s.iter().flat_map(|point| g1_le(*point)));
+        assert_eq!(uncompressed.len(), EXPECTED_PROOF_SIZE);
+
+        let mut compressed = G1AffineLE::from(zkp.proof.a).to_compressed().to_vec();
+        compressed.extend(G2AffineLE::from(zkp.proof.b).to_compressed());
+        compressed.extend(g1s.iter().flat_map(|point| G1AffineLE::from(*point).to_compressed()));
+
+        for bytes in [uncompressed, compressed] {
+            let decoded = ZKP::<LittleEndian>::try_from(&bytes[..]).unwrap();
+            assert!(decoded.proof.a == zkp.proof.a);
+            assert!(decoded.proof.b == zkp.proof.b);
+            assert!(decoded.proof.c == zkp.proof.c);
+            assert!(decoded.proof_commitments == zkp.proof_commitments);
+            assert!(decoded.proof_commitment_pok == zkp.proof_commitment_pok);
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! gnark compatible Groth16 verification, for any number of public inputs and Pedersen
+//! commitments, over any [`Pairing`].
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::Digest;
+use substrate_bn::Fr;
+
+use crate::{
+    curve::{Bn254, Pairing},
+    hash_to_field, Error, PRIME_R_MINUS_ONE, ZKP,
+};
+
+/// gnark's domain separation tag of the commitment hash.
+pub const COMMITMENT_DST: &[u8] = b"bsb22-commitment";
+/// gnark's domain separation tag of the challenge folding the commitments.
+pub const FOLDING_DST: &[u8] = b"G16-BSB22";
+
+/// The key the proof of knowledge of the Pedersen commitments is verified with.
+#[derive(Clone, Copy)]
+pub struct CommitmentKey<E: Pairing = Bn254> {
+    pub g: E::G2,
+    pub g_root_sigma_neg: E::G2,
+}
+
+/// A verification key in the Groth16 SNARK over `E`.
+pub struct VerifyingKey<E: Pairing = Bn254> {
+    /// The `alpha * G`, where `G` is the generator of `E::G1`.
+    pub alpha_g1: E::G1,
+    /// The `alpha * H`, where `H` is the generator of `E::G2`.
+    pub beta_g2: E::G2,
+    /// The `gamma * H`, where `H` is the generator of `E::G2`.
+    pub gamma_g2: E::G2,
+    /// The `delta * H`, where `H` is the generator of `E::G2`.
+    pub delta_g2: E::G2,
+    /// The `gamma^{-1} * (beta * a_i + alpha * b_i + c_i) * H`, where `H` is the generator of `E::G1`.
+    pub gamma_abc_g1: Vec<E::G1>,
+    /// The key of the Pedersen commitments, `None` if the circuit has none.
+    pub commitment_key: Option<CommitmentKey<E>>,
+    /// The public inputs every commitment commits to, by their index in the public inputs. gnark
+    /// counts the constant one, so its indices are one larger.
+    pub public_and_commitment_committed: Vec<Vec<usize>>,
+    pub commitment_hash: CommitmentHash,
+}
+
+pub struct Proof<E: Pairing = Bn254> {
+    /// The `A` element in `G1`.
+    pub a: E::G1,
+    /// The `B` element in `G2`.
+    pub b: E::G2,
+    /// The `C` element in `G1`.
+    pub c: E::G1,
+}
+
+/// How a commitment, followed by the public inputs it commits to, is hashed into the public
+/// input it adds. The prover has to be configured with the same hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommitmentHash {
+    /// gnark's default, `expand_message_xmd` with SHA-256 and [`COMMITMENT_DST`].
+    Gnark,
+    /// CometBLS' HMAC-Keccak, see the whitepaper.
+    CometBls,
+}
+
+impl CommitmentHash {
+    fn hash<E: Pairing>(self, message: &[u8]) -> Result<E::Scalar, Error> {
+        match self {
+            CommitmentHash::Gnark => Ok(E::hash_to_scalar(message, COMMITMENT_DST)),
+            CommitmentHash::CometBls => E::scalar_from_bytes(hash_to_field(message).to_be_bytes())
+                .ok_or(Error::InvalidCommitment),
+        }
+    }
+}
+
+/// Verifies `zkp` for `public_inputs`, without the constant one gnark prepends.
+///
+/// Like gnark, every commitment of the proof adds the hash of itself and the public inputs
+/// [`VerifyingKey::public_and_commitment_committed`] lists for it as a public input, after
+/// `public_inputs`, and is added to the public inputs' linear combination. The commitments are
+/// proven with a single proof of knowledge, folded with a challenge if there are several.
+pub fn verify_groth16<FromOrder>(
+    vk: &VerifyingKey,
+    zkp: &ZKP<FromOrder>,
+    public_inputs: &[Fr],
+) -> Result<(), Error> {
+    verify(
+        vk,
+        &zkp.proof,
+        &zkp.proof_commitments,
+        zkp.proof_commitment_pok,
+        public_inputs,
+    )
+}
+
+/// [`verify_groth16`] over any curve, with the proof's commitments and their proof of knowledge
+/// apart from it.
+pub(crate) fn verify<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    proof_commitments: &[E::G1],
+    proof_commitment_pok: Option<E::G1>,
+    public_inputs: &[E::Scalar],
+) -> Result<(), Error> {
+    if vk.public_and_commitment_committed.len() != proof_commitments.len() {
+        return Err(Error::InvalidCommitment);
+    }
+    if vk.gamma_abc_g1.len() != 1 + public_inputs.len() + proof_commitments.len() {
+        return Err(Error::InvalidPublicInput);
+    }
+
+    let mut commitment_hashes = Vec::with_capacity(proof_commitments.len());
+    for (commitment, committed) in proof_commitments
+        .iter()
+        .zip(&vk.public_and_commitment_committed)
+    {
+        let mut message = E::g1_bytes(commitment)?;
+        for index in committed {
+            let input = public_inputs.get(*index).ok_or(Error::InvalidVerifyingKey)?;
+            message.extend(E::scalar_bytes(input));
+        }
+        commitment_hashes.push(vk.commitment_hash.hash::<E>(&message)?);
+    }
+
+    if let Some(commitment) = fold_commitments::<E>(proof_commitments, &commitment_hashes) {
+        let key = vk.commitment_key.as_ref().ok_or(Error::InvalidVerifyingKey)?;
+        let pok = proof_commitment_pok.ok_or(Error::InvalidPok)?;
+        if !E::pairing_product_is_one(&[
+            (commitment, key.g),
+            (pok.into(), key.g_root_sigma_neg),
+        ]) {
+            return Err(Error::InvalidPok);
+        }
+    }
+
+    let initial_point = proof_commitments
+        .iter()
+        .fold(E::G1Sum::from(vk.gamma_abc_g1[0]), |s, commitment| {
+            s + E::G1Sum::from(*commitment)
+        });
+    let public_inputs_msm = public_inputs
+        .iter()
+        .chain(&commitment_hashes)
+        .zip(&vk.gamma_abc_g1[1..])
+        .fold(initial_point, |s, (w_i, gamma_l_i)| {
+            s + E::G1Sum::from(*gamma_l_i) * *w_i
+        });
+
+    // `e(a, b) = e(alpha, beta) * e(msm, gamma) * e(c, delta)`.
+    if E::pairing_product_is_one(&[
+        (proof.a.into(), proof.b),
+        (-public_inputs_msm, vk.gamma_g2),
+        (-E::G1Sum::from(proof.c), vk.delta_g2),
+        (-E::G1Sum::from(vk.alpha_g1), vk.beta_g2),
+    ]) {
+        Ok(())
+    } else {
+        Err(Error::InvalidProof)
+    }
+}
+
+/// `sum(r^i * commitments[i])`, where the challenge `r` is the hash of the commitment hashes,
+/// `None` without commitments.
+fn fold_commitments<E: Pairing>(
+    commitments: &[E::G1],
+    hashes: &[E::Scalar],
+) -> Option<E::G1Sum> {
+    let (last, rest) = commitments.split_last()?;
+    if rest.is_empty() {
+        return Some((*last).into());
+    }
+    let challenge = E::hash_to_scalar(
+        &hashes.iter().flat_map(E::scalar_bytes).collect::<Vec<_>>(),
+        FOLDING_DST,
+    );
+    // Horner's rule, from the last commitment.
+    Some(rest.iter().rev().fold(E::G1Sum::from(*last), |folded, commitment| {
+        folded * challenge + E::G1Sum::from(*commitment)
+    }))
+}
+
+/// gnark's `fr.Hash(message, dst, 1)`: 48 bytes of `expand_message_xmd` (RFC 9380) with
+/// SHA-256, reduced modulo `r`.
+pub fn hash_to_fr(message: &[u8], dst: &[u8]) -> Fr {
+    let mut bytes = [0; 64];
+    expand_message_xmd(message, dst, &mut bytes[16..]);
+    let r = primitive_types::U512::from(PRIME_R_MINUS_ONE.0) + primitive_types::U512::one();
+    let reduced =
+        primitive_types::U256::try_from(primitive_types::U512::from_big_endian(&bytes) % r)
+            .expect("reduced modulo r; qed;");
+    Fr::new(reduced.0.into()).expect("reduced modulo r; qed;")
+}
+
+/// Fills `out`, at most 255 blocks of 32 bytes, with `expand_message_xmd(message, dst)`.
+pub(crate) fn expand_message_xmd(message: &[u8], dst: &[u8], out: &mut [u8]) {
+    const B_IN_BYTES: usize = 32;
+    const S_IN_BYTES: usize = 64;
+
+    let dst_len = [u8::try_from(dst.len()).expect("tags are at most 255 bytes; qed;")];
+    let len = u16::try_from(out.len()).expect("at most 255 blocks are expanded; qed;");
+    let b_0 = Sha256::new()
+        .chain_update([0; S_IN_BYTES])
+        .chain_update(message)
+        .chain_update(len.to_be_bytes())
+        .chain_update([0])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut b_i = [0; B_IN_BYTES];
+    for (i, chunk) in out.chunks_mut(B_IN_BYTES).enumerate() {
+        for (b, b_0) in b_i.iter_mut().zip(&b_0) {
+            *b ^= b_0;
+        }
+        b_i = Sha256::new()
+            .chain_update(b_i)
+            .chain_update([i as u8 + 1])
+            .chain_update(dst)
+            .chain_update(dst_len)
+            .finalize()
+            .into();
+        chunk.copy_from_slice(&b_i[..chunk.len()]);
+    }
+}
+
+pub(crate) fn fr_bytes(fr: &Fr) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    fr.to_big_endian(&mut bytes).expect("an Fr is 32 bytes; qed;");
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::marker::PhantomData;
+
+    use byteorder::BigEndian;
+    use hex_literal::hex;
+
+    use super::*;
+    use crate::{
+        tests::{groth16_instance, Instance, KnownLogs},
+        VerifyingKey,
+    };
+
+    fn scalar(value: u64) -> Fr {
+        Bn254::scalar(value)
+    }
+
+    fn instance(
+        public_inputs: &[Fr],
+        committed: Vec<Vec<usize>>,
+    ) -> (VerifyingKey, ZKP<BigEndian>) {
+        let Instance {
+            vk,
+            proof,
+            commitments,
+            pok,
+        } = groth16_instance::<Bn254>(public_inputs, committed);
+        let zkp = ZKP {
+            proof,
+            proof_commitments: commitments,
+            proof_commitment_pok: pok,
+            _marker: PhantomData,
+        };
+        (vk, zkp)
+    }
+
+    #[test]
+    fn test_verify_groth16() {
+        let inputs = [scalar(1), scalar(2), scalar(3)];
+        let (vk, zkp) = instance(&inputs, vec![]);
+        assert_eq!(verify_groth16(&vk, &zkp, &inputs), Ok(()));
+        assert_eq!(
+            verify_groth16(&vk, &zkp, &[scalar(1), scalar(2), scalar(4)]),
+            Err(Error::InvalidProof)
+        );
+        assert_eq!(
+            verify_groth16(&vk, &zkp, &inputs[..2]),
+            Err(Error::InvalidPublicInput)
+        );
+    }
+
+    #[test]
+    fn test_verify_groth16_commitments() {
+        let inputs = [scalar(1), scalar(2)];
+        for committed in [vec![vec![]], vec![vec![1], vec![0, 1]]] {
+            let (vk, zkp) = instance(&inputs, committed);
+            assert_eq!(verify_groth16(&vk, &zkp, &inputs), Ok(()));
+            assert_eq!(
+                verify_groth16(&vk, &zkp, &[scalar(1), scalar(3)]),
+                Err(Error::InvalidProof)
+            );
+
+            let mut tampered = zkp;
+            tampered.proof_commitment_pok = Some(Bn254::g1(scalar(5)));
+            assert_eq!(verify_groth16(&vk, &tampered, &inputs), Err(Error::InvalidPok));
+            tampered.proof_commitments.pop();
+            assert_eq!(
+                verify_groth16(&vk, &tampered, &inputs),
+                Err(Error::InvalidCommitment)
+            );
+        }
+    }
+
+    /// Generated by gnark with `fixtures/gnark`: a proof with two Pedersen commitments, each to a
+    /// public and a private input.
+    fn gnark_fixture() -> (VerifyingKey, ZKP<BigEndian>, Vec<Fr>) {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../fixtures/groth16/commitments.json");
+        let json = std::fs::read(path)
+            .unwrap_or_else(|err| panic!("{path}: {err}, generate it with fixtures/gnark"));
+        let fixture: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let public_inputs = fixture["public_inputs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|input| Fr::from_slice(&hex::decode(input.as_str().unwrap()).unwrap()).unwrap())
+            .collect();
+        (
+            serde_json::from_value(fixture["verifying_key"].clone()).unwrap(),
+            serde_json::from_value(fixture["proof"].clone()).unwrap(),
+            public_inputs,
+        )
+    }
+
+    #[test]
+    #[ignore = "needs fixtures/groth16/commitments.json, which is generated with gnark"]
+    fn test_gnark_proof() {
+        let (vk, zkp, inputs) = gnark_fixture();
+        assert_eq!(zkp.proof_commitments.len(), 2);
+        assert_eq!(vk.public_and_commitment_committed, vec![vec![0], vec![1]]);
+        assert_eq!(verify_groth16(&vk, &zkp, &inputs), Ok(()));
+
+        // The public inputs are committed, so the folded commitment changes too.
+        let mut tampered = inputs.clone();
+        tampered[0] = tampered[0] + Fr::one();
+        assert_eq!(verify_groth16(&vk, &zkp, &tampered), Err(Error::InvalidPok));
+
+        let mut tampered = gnark_fixture().1;
+        tampered.proof.c = tampered.proof.a;
+        assert_eq!(verify_groth16(&vk, &tampered, &inputs), Err(Error::InvalidProof));
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // RFC 9380, K.1.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let mut out = [0; 32];
+        expand_message_xmd(b"", dst, &mut out);
+        assert_eq!(
+            out,
+            hex!("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")
+        );
+        expand_message_xmd(b"abc", dst, &mut out);
+        assert_eq!(
+            out,
+            hex!("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615")
+        );
+    }
+}
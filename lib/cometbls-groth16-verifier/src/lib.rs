@@ -2,18 +2,24 @@
 
 extern crate alloc;
 
-use crate::{bounded::{BoundedI32, BoundedI64}, constants::*};
+use crate::{
+    bounded::{BoundedI32, BoundedI64},
+    compressed::{G1_COMPRESSED_SIZE, G2_COMPRESSED_SIZE},
+    constants::*,
+    curve::{Bn254, Curve},
+};
 
 use alloc::vec::Vec;
 use core::{marker::PhantomData, ops::{Add, Rem}};
 
 use ark_ff::vec;
-use byteorder::{BigEndian, ByteOrder};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
 // use constants::*;
 use hex_literal::hex;
 use serde::{Deserialize, Serialize};
 use sha3::Digest;
-use substrate_bn::G1;
+
+pub use crate::groth16::{verify_groth16, CommitmentHash};
 
 type H256 = [u8; 32];
 pub struct U256(pub primitive_types::U256);
@@ -40,6 +46,29 @@ pub struct VerifyZkpRequest {
 
     pub zkp: Vec<u8>,
 }
+
+impl VerifyZkpRequest {
+    /// The SHA-256 of every field, which the verifier guest commits next to its result so the
+    /// receipt binds the request it verified. Variable length fields are prefixed with their
+    /// length as a big endian `u32`, integers are big endian.
+    pub fn digest(&self) -> H256 {
+        sha2::Sha256::new()
+            .chain_update((self.chain_id.len() as u32).to_be_bytes())
+            .chain_update(self.chain_id.as_bytes())
+            .chain_update(self.trusted_validators_hash)
+            .chain_update(self.height.to_be_bytes())
+            .chain_update(self.validators_hash)
+            .chain_update(self.next_validators_hash)
+            .chain_update(self.app_hash)
+            .chain_update(self.seconds.to_be_bytes())
+            .chain_update(self.nanos.to_be_bytes())
+            .chain_update((self.zkp.len() as u32).to_be_bytes())
+            .chain_update(&self.zkp)
+            .finalize()
+            .into()
+    }
+}
+
 pub fn handle_verify_zkp_request(request: VerifyZkpRequest) -> Result<(), crate::Error> {
     verify_zkp(
         request.chain_id.as_str(),
@@ -58,6 +87,7 @@ pub fn handle_verify_zkp_request(request: VerifyZkpRequest) -> Result<(), crate:
     )
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Timestamp {
     /// As per the proto docs: "Must be from 0001-01-01T00:00:00Z to
     /// 9999-12-31T23:59:59Z inclusive."
@@ -66,11 +96,16 @@ pub struct Timestamp {
     pub nanos: BoundedI32<0, NANOS_MAX>,
 }
 
+/// Serialized with hex encoded hashes, see [`codec`] for the other encodings.
+#[derive(Serialize, Deserialize)]
 pub struct LightHeader {
     pub height: BoundedI64<0, { i64::MAX }>,
     pub time: Timestamp,
+    #[serde(with = "hex::serde")]
     pub validators_hash: H256,
+    #[serde(with = "hex::serde")]
     pub next_validators_hash: H256,
+    #[serde(with = "hex::serde")]
     pub app_hash: H256,
 }
 
@@ -135,6 +170,15 @@ impl<const N: usize> ByteArrayExt<N> for [u8; N] {
     }
 }
 
+pub mod abi;
+pub mod bls;
+pub mod codec;
+pub mod compressed;
+pub mod curve;
+pub mod encoding;
+pub mod groth16;
+pub mod plonk;
+pub mod risc0;
 mod constants;
 mod uint;
 mod errors;
@@ -181,26 +225,13 @@ fn hash_to_field(message: &[u8]) -> U256 {
     (U256::from_be_bytes(hmac_keccak(message)) % PRIME_R_MINUS_ONE) + U256::from(1)
 }
 
-// Gnark commitment hashing, we employ our custom hash_to_field in the prover itself
-fn hash_commitment(proof_commitment: &substrate_bn::AffineG1) -> Result<U256, Error> {
-    let mut buffer = [0u8; 64];
-    proof_commitment
-        .x()
-        .to_big_endian(&mut buffer[0..32])
-        .map_err(|_| Error::InvalidCommitment)?;
-    proof_commitment
-        .y()
-        .to_big_endian(&mut buffer[32..64])
-        .map_err(|_| Error::InvalidCommitment)?;
-    Ok(hash_to_field(&buffer))
-}
-
-pub const FQ_SIZE: usize = 32;
-pub const G1_SIZE: usize = 2 * FQ_SIZE;
-pub const G2_SIZE: usize = 2 * G1_SIZE;
+pub const FQ_SIZE: usize = Bn254::FQ_SIZE;
+pub const G1_SIZE: usize = Bn254::G1_SIZE;
+pub const G2_SIZE: usize = Bn254::G2_SIZE;
 
 pub struct G1Affine<FromOrder: ByteOrder>(PhantomData<FromOrder>, substrate_bn::AffineG1);
 pub type G1AffineBE = G1Affine<BigEndian>;
+pub type G1AffineLE = G1Affine<LittleEndian>;
 
 impl TryFrom<[u8; G1_SIZE]> for G1AffineBE {
     type Error = Error;
@@ -220,6 +251,7 @@ impl TryFrom<[u8; G1_SIZE]> for G1AffineBE {
 
 pub struct G2Affine<FromOrder>(PhantomData<FromOrder>, substrate_bn::AffineG2);
 pub type G2AffineBE = G2Affine<BigEndian>;
+pub type G2AffineLE = G2Affine<LittleEndian>;
 
 impl TryFrom<[u8; G2_SIZE]> for G2AffineBE {
     type Error = Error;
@@ -247,70 +279,119 @@ impl TryFrom<[u8; G2_SIZE]> for G2AffineBE {
     }
 }
 
-/// A verification key in the Groth16 SNARK.
-pub struct VerifyingKey {
-    /// The `alpha * G`, where `G` is the generator of `E::G1`.
-    pub alpha_g1: substrate_bn::AffineG1,
-    /// The `alpha * H`, where `H` is the generator of `E::G2`.
-    pub beta_g2: substrate_bn::AffineG2,
-    /// The `gamma * H`, where `H` is the generator of `E::G2`.
-    pub gamma_g2: substrate_bn::AffineG2,
-    /// The `delta * H`, where `H` is the generator of `E::G2`.
-    pub delta_g2: substrate_bn::AffineG2,
-    /// The `gamma^{-1} * (beta * a_i + alpha * b_i + c_i) * H`, where `H` is the generator of `E::G1`.
-    pub gamma_abc_g1: Vec<substrate_bn::AffineG1>,
-}
-
-pub struct Proof {
-    /// The `A` element in `G1`.
-    pub a: substrate_bn::AffineG1,
-    /// The `B` element in `G2`.
-    pub b: substrate_bn::AffineG2,
-    /// The `C` element in `G1`.
-    pub c: substrate_bn::AffineG1,
-}
+/// A verification key in the Groth16 SNARK over BN254.
+pub type VerifyingKey = groth16::VerifyingKey<Bn254>;
+/// The key the proof of knowledge of BN254 Pedersen commitments is verified with.
+pub type CommitmentKey = groth16::CommitmentKey<Bn254>;
+pub type Proof = groth16::Proof<Bn254>;
 
 pub struct ZKP<FromOrder> {
     pub proof: Proof,
-    pub proof_commitment: substrate_bn::AffineG1,
-    pub proof_commitment_pok: substrate_bn::AffineG1,
+    /// gnark's Pedersen commitments, CometBLS proofs have exactly one.
+    pub proof_commitments: Vec<substrate_bn::AffineG1>,
+    /// The proof of knowledge of all commitments, `None` without commitments.
+    pub proof_commitment_pok: Option<substrate_bn::AffineG1>,
     pub _marker: PhantomData<FromOrder>,
 }
 
+impl<FromOrder> ZKP<FromOrder> {
+    /// The commitment and its proof of knowledge of a CometBLS proof.
+    pub fn cometbls_commitment(
+        &self,
+    ) -> Result<(&substrate_bn::AffineG1, &substrate_bn::AffineG1), Error> {
+        match (&self.proof_commitments[..], &self.proof_commitment_pok) {
+            ([commitment], Some(pok)) => Ok((commitment, pok)),
+            _ => Err(Error::InvalidCommitment),
+        }
+    }
+}
+
 // G1 + G2 + G1 + G1 + G1
-pub const EXPECTED_PROOF_SIZE: usize = G1_SIZE + G2_SIZE + G1_SIZE + G1_SIZE + G1_SIZE;
+pub const EXPECTED_PROOF_SIZE: usize = Bn254::PROOF_SIZE;
+// The same points, compressed.
+pub const COMPRESSED_PROOF_SIZE: usize = Bn254::COMPRESSED_PROOF_SIZE;
 
 // [a ... b ... c ... proof_commitment ... commitment_pok]
 pub type RawZKP = [u8; EXPECTED_PROOF_SIZE];
+pub type RawCompressedZKP = [u8; COMPRESSED_PROOF_SIZE];
 
+/// Accepts the uncompressed [`RawZKP`] and gnark's compressed [`RawCompressedZKP`], told apart by
+/// the length. Both have exactly one commitment, use [`ZKP::from_gnark`] for any other number.
 impl<FromOrder: ByteOrder> TryFrom<&[u8]> for ZKP<FromOrder>
 where
-    G1Affine<FromOrder>: TryFrom<[u8; G1_SIZE], Error = Error>,
-    G2Affine<FromOrder>: TryFrom<[u8; G2_SIZE], Error = Error>,
+    G1Affine<FromOrder>: TryFrom<[u8; G1_SIZE], Error = Error>
+        + TryFrom<[u8; G1_COMPRESSED_SIZE], Error = Error>,
+    G2Affine<FromOrder>: TryFrom<[u8; G2_SIZE], Error = Error>
+        + TryFrom<[u8; G2_COMPRESSED_SIZE], Error = Error>,
 {
     type Error = Error;
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if let Ok(value) = RawCompressedZKP::try_from(value) {
+            return Self::from_compressed(value);
+        }
         let value = RawZKP::try_from(value).map_err(|_| Error::InvalidRawProof)?;
-        let G1Affine(_, a) = G1Affine::<FromOrder>::try_from(value.array_slice::<0, G1_SIZE>())?;
+        Self::from_gnark(&value)
+    }
+}
+
+impl<FromOrder: ByteOrder> ZKP<FromOrder>
+where
+    G1Affine<FromOrder>: TryFrom<[u8; G1_SIZE], Error = Error>,
+    G2Affine<FromOrder>: TryFrom<[u8; G2_SIZE], Error = Error>,
+{
+    /// Decodes an uncompressed proof with any number of commitments, see
+    /// [`Curve::proof_commitments`], as [`verify_groth16`] accepts them.
+    pub fn from_gnark(value: &[u8]) -> Result<Self, Error> {
+        let commitments = Bn254::proof_commitments(value.len()).ok_or(Error::InvalidRawProof)?;
+        let (proof, rest) = value.split_at(G1_SIZE + G2_SIZE + G1_SIZE);
+        let proof: [u8; G1_SIZE + G2_SIZE + G1_SIZE] =
+            proof.try_into().expect("split at the size of a proof; qed;");
+        let G1Affine(_, a) = G1Affine::<FromOrder>::try_from(proof.array_slice::<0, G1_SIZE>())?;
         let G2Affine(_, b) =
-            G2Affine::<FromOrder>::try_from(value.array_slice::<G1_SIZE, G2_SIZE>())?;
+            G2Affine::<FromOrder>::try_from(proof.array_slice::<G1_SIZE, G2_SIZE>())?;
         let G1Affine(_, c) =
-            G1Affine::<FromOrder>::try_from(value.array_slice::<{ G1_SIZE + G2_SIZE }, G1_SIZE>())?;
-        let G1Affine(_, proof_commitment) = G1Affine::<FromOrder>::try_from(
-            value.array_slice::<{ G1_SIZE + G2_SIZE + G1_SIZE }, G1_SIZE>(),
-        )?;
-        let G1Affine(_, proof_commitment_pok) = G1Affine::<FromOrder>::try_from(
-            value.array_slice::<{ G1_SIZE + G2_SIZE + G1_SIZE + G1_SIZE }, G1_SIZE>(),
-        )?;
+            G1Affine::<FromOrder>::try_from(proof.array_slice::<{ G1_SIZE + G2_SIZE }, G1_SIZE>())?;
+
+        let mut points = rest.chunks_exact(G1_SIZE).map(|point| {
+            let point = <[u8; G1_SIZE]>::try_from(point).expect("chunks of G1_SIZE; qed;");
+            G1Affine::<FromOrder>::try_from(point).map(|G1Affine(_, point)| point)
+        });
+        let proof_commitments = points.by_ref().take(commitments).collect::<Result<_, _>>()?;
+        let proof_commitment_pok = points.next().transpose()?;
         Ok(Self {
             proof: Proof { a, b, c },
-            proof_commitment,
+            proof_commitments,
             proof_commitment_pok,
             _marker: PhantomData,
         })
     }
 }
 
+impl<FromOrder: ByteOrder> ZKP<FromOrder>
+where
+    G1Affine<FromOrder>: TryFrom<[u8; G1_COMPRESSED_SIZE], Error = Error>,
+    G2Affine<FromOrder>: TryFrom<[u8; G2_COMPRESSED_SIZE], Error = Error>,
+{
+    fn from_compressed(value: RawCompressedZKP) -> Result<Self, Error> {
+        const C1: usize = G1_COMPRESSED_SIZE;
+        const C2: usize = G2_COMPRESSED_SIZE;
+        let G1Affine(_, a) = G1Affine::<FromOrder>::try_from(value.array_slice::<0, C1>())?;
+        let G2Affine(_, b) = G2Affine::<FromOrder>::try_from(value.array_slice::<C1, C2>())?;
+        let G1Affine(_, c) =
+            G1Affine::<FromOrder>::try_from(value.array_slice::<{ C1 + C2 }, C1>())?;
+        let G1Affine(_, proof_commitment) =
+            G1Affine::<FromOrder>::try_from(value.array_slice::<{ 2 * C1 + C2 }, C1>())?;
+        let G1Affine(_, proof_commitment_pok) =
+            G1Affine::<FromOrder>::try_from(value.array_slice::<{ 3 * C1 + C2 }, C1>())?;
+        Ok(Self {
+            proof: Proof { a, b, c },
+            proof_commitments: vec![proof_commitment],
+            proof_commitment_pok: Some(proof_commitment_pok),
+            _marker: PhantomData,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Error {
     InvalidPublicInput,
@@ -324,6 +405,8 @@ pub enum Error {
     InvalidHeight,
     InvalidTimestamp,
     InvalidSliceLength,
+    InvalidAbi,
+    InvalidHex,
 }
 
 pub fn verify_zkp(
@@ -353,11 +436,9 @@ fn verify_generic_zkp_2(
     if chain_id.len() > 31 {
         return Err(Error::InvalidChainId);
     }
-    // Constant + public inputs
     let decode_scalar = move |x: U256| -> Result<substrate_bn::Fr, Error> {
         substrate_bn::Fr::new(x.0 .0.into()).ok_or(Error::InvalidPublicInput)
     };
-    let commitment_hash = hash_commitment(&zkp.proof_commitment)?;
     let mut inputs_hash = <[u8; 32]>::from(
         sha2::Sha256::new()
             .chain_update(
@@ -394,35 +475,268 @@ fn verify_generic_zkp_2(
     );
     // drop the most significant byte to fit in bn254 F_r
     inputs_hash[0] = 0;
-    let public_inputs: [substrate_bn::Fr; NB_PUBLIC_INPUTS] = [
-        decode_scalar(U256::from_be_bytes(inputs_hash))?,
-        decode_scalar(commitment_hash)?,
-    ];
-    let initial_point = substrate_bn::G1::from(GAMMA_ABC_G1[0]) + zkp.proof_commitment.into();
-    let public_inputs_msm = public_inputs
-        .into_iter()
-        .zip(GAMMA_ABC_G1.into_iter().skip(1).map(substrate_bn::G1::from))
-        .fold(initial_point, |s, (w_i, gamma_l_i)| s + gamma_l_i * w_i);
-
-    let proof_a: G1 = zkp.proof.a.into();
-    let proof_c: G1 = zkp.proof.c.into();
-    let pc: G1 = zkp.proof_commitment.into();
-    let pok: G1 = zkp.proof_commitment_pok.into();
-
-    let pok_result = substrate_bn::pairing_batch(&[(pc, g.into()), (pok, g_root_sigma_neg.into())]);
-    if pok_result != substrate_bn::Gt::one() {
-        return Err(Error::InvalidPok);
+    // The hash of the commitment, the second public input, is added by `verify_groth16`.
+    let public_inputs = [decode_scalar(U256::from_be_bytes(inputs_hash))?];
+    zkp.cometbls_commitment()?;
+
+    let vk = VerifyingKey {
+        alpha_g1: ALPHA_G1,
+        beta_g2: BETA_G2,
+        gamma_g2: GAMMA_G2,
+        delta_g2: DELTA_G2,
+        gamma_abc_g1: GAMMA_ABC_G1.to_vec(),
+        commitment_key: Some(CommitmentKey { g, g_root_sigma_neg }),
+        public_and_commitment_committed: vec![vec![]],
+        commitment_hash: CommitmentHash::CometBls,
+    };
+    verify_groth16(&vk, &zkp, &public_inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use substrate_bn::Group;
+
+    use super::*;
+    use crate::{
+        curve::{Bls12_381, Pairing},
+        groth16::{COMMITMENT_DST, FOLDING_DST},
+    };
+
+    /// The scalars and generators of a curve, to build proofs from known discrete logarithms.
+    pub(crate) trait KnownLogs: Pairing {
+        fn scalar(value: u64) -> Self::Scalar;
+        fn inverse(scalar: Self::Scalar) -> Self::Scalar;
+        fn g1(scalar: Self::Scalar) -> Self::G1;
+        fn g2(scalar: Self::Scalar) -> Self::G2;
+    }
+
+    impl KnownLogs for Bn254 {
+        fn scalar(value: u64) -> Self::Scalar {
+            substrate_bn::Fr::from_str(&value.to_string()).unwrap()
+        }
+
+        fn inverse(scalar: Self::Scalar) -> Self::Scalar {
+            scalar.inverse().unwrap()
+        }
+
+        fn g1(scalar: Self::Scalar) -> Self::G1 {
+            substrate_bn::AffineG1::from_jacobian(substrate_bn::G1::one() * scalar).unwrap()
+        }
+
+        fn g2(scalar: Self::Scalar) -> Self::G2 {
+            substrate_bn::AffineG2::from_jacobian(substrate_bn::G2::one() * scalar).unwrap()
+        }
+    }
+
+    impl KnownLogs for Bls12_381 {
+        fn scalar(value: u64) -> Self::Scalar {
+            bls12_381::Scalar::from(value)
+        }
+
+        fn inverse(scalar: Self::Scalar) -> Self::Scalar {
+            scalar.invert().unwrap()
+        }
+
+        fn g1(scalar: Self::Scalar) -> Self::G1 {
+            (bls12_381::G1Affine::generator() * scalar).into()
+        }
+
+        fn g2(scalar: Self::Scalar) -> Self::G2 {
+            (bls12_381::G2Affine::generator() * scalar).into()
+        }
+    }
+
+    /// A verifying key, and a proof with its commitments and their proof of knowledge.
+    pub(crate) struct Instance<E: Pairing> {
+        pub vk: groth16::VerifyingKey<E>,
+        pub proof: groth16::Proof<E>,
+        pub commitments: Vec<E::G1>,
+        pub pok: Option<E::G1>,
+    }
+
+    /// A Groth16 proof of `public_inputs` with a commitment for every entry of `committed`,
+    /// built from known discrete logarithms such that
+    /// `a * b = alpha * beta + l * gamma + c * delta`, with gnark's commitment hash.
+    pub(crate) fn groth16_instance<E: KnownLogs>(
+        public_inputs: &[E::Scalar],
+        committed: Vec<Vec<usize>>,
+    ) -> Instance<E> {
+        let [alpha, beta, gamma, delta, sigma, a, b] = [3, 5, 7, 11, 13, 17, 19].map(E::scalar);
+        let ic: Vec<_> = (0..1 + public_inputs.len() + committed.len())
+            .map(|i| E::scalar(23 + i as u64))
+            .collect();
+        let commitments: Vec<_> = (0..committed.len()).map(|i| E::scalar(101 + i as u64)).collect();
+
+        let vk = groth16::VerifyingKey::<E> {
+            alpha_g1: E::g1(alpha),
+            beta_g2: E::g2(beta),
+            gamma_g2: E::g2(gamma),
+            delta_g2: E::g2(delta),
+            gamma_abc_g1: ic.iter().copied().map(E::g1).collect(),
+            commitment_key: Some(groth16::CommitmentKey {
+                g: E::g2(E::scalar(1)),
+                g_root_sigma_neg: E::g2(-sigma),
+            }),
+            public_and_commitment_committed: committed,
+            commitment_hash: CommitmentHash::Gnark,
+        };
+        let proof_commitments: Vec<_> = commitments.iter().copied().map(E::g1).collect();
+
+        // The hashes only depend on the commitments and the public inputs.
+        let hashes: Vec<_> = proof_commitments
+            .iter()
+            .zip(&vk.public_and_commitment_committed)
+            .map(|(commitment, committed)| {
+                let mut message = E::g1_bytes(commitment).unwrap();
+                for index in committed {
+                    message.extend(E::scalar_bytes(&public_inputs[*index]));
+                }
+                E::hash_to_scalar(&message, COMMITMENT_DST)
+            })
+            .collect();
+        let pok = (!commitments.is_empty()).then(|| {
+            let challenge = E::hash_to_scalar(
+                &hashes.iter().flat_map(E::scalar_bytes).collect::<Vec<_>>(),
+                FOLDING_DST,
+            );
+            let (folded, _) = commitments.iter().fold(
+                (E::scalar(0), E::scalar(1)),
+                |(folded, power), commitment| (folded + *commitment * power, power * challenge),
+            );
+            E::g1(folded * E::inverse(sigma))
+        });
+
+        let l = public_inputs
+            .iter()
+            .chain(&hashes)
+            .zip(&ic[1..])
+            .fold(ic[0], |l, (input, ic)| l + *input * *ic);
+        let l = commitments.iter().fold(l, |l, commitment| l + *commitment);
+        let c = (a * b - alpha * beta - l * gamma) * E::inverse(delta);
+        Instance {
+            vk,
+            proof: groth16::Proof {
+                a: E::g1(a),
+                b: E::g2(b),
+                c: E::g1(c),
+            },
+            commitments: proof_commitments,
+            pok,
+        }
+    }
+
+    /// The subset of the host's fixture format needed to run it natively.
+    #[derive(Deserialize)]
+    struct Fixture {
+        request: FixtureRequest,
+        expected: Expected,
+    }
+
+    #[derive(Deserialize)]
+    struct FixtureRequest {
+        chain_id: String,
+        #[serde(with = "hex")]
+        trusted_validators_hash: H256,
+        height: i64,
+        seconds: i64,
+        nanos: i32,
+        #[serde(with = "hex")]
+        validators_hash: H256,
+        #[serde(with = "hex")]
+        next_validators_hash: H256,
+        #[serde(with = "hex")]
+        app_hash: H256,
+        #[serde(with = "hex")]
+        zkp: Vec<u8>,
+    }
+
+    #[derive(Deserialize)]
+    struct Expected {
+        valid: bool,
+        error: Option<String>,
+    }
+
+    impl From<FixtureRequest> for VerifyZkpRequest {
+        fn from(request: FixtureRequest) -> Self {
+            Self {
+                chain_id: request.chain_id,
+                trusted_validators_hash: request.trusted_validators_hash,
+                height: request.height,
+                validators_hash: request.validators_hash,
+                next_validators_hash: request.next_validators_hash,
+                app_hash: request.app_hash,
+                seconds: request.seconds,
+                nanos: request.nanos,
+                zkp: request.zkp,
+            }
+        }
+    }
+
+    /// The uncompressed proof of `fixtures/valid_block_969001.json`.
+    pub(crate) fn fixture_zkp() -> RawZKP {
+        let fixture: Fixture =
+            serde_json::from_str(include_str!("../../../fixtures/valid_block_969001.json"))
+                .unwrap();
+        fixture.request.zkp.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures");
+        let mut count = 0;
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let fixture: Fixture = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+
+            let result = handle_verify_zkp_request(fixture.request.into());
+
+            match (result, fixture.expected) {
+                (Ok(()), Expected { valid: true, .. }) => {}
+                (Err(err), Expected { valid: false, error: Some(error) }) => {
+                    assert_eq!(format!("{err:?}"), error, "{}", path.display());
+                }
+                (result, _) => panic!("{}: unexpected result {result:?}", path.display()),
+            }
+            count += 1;
+        }
+        assert!(count > 0, "no fixtures found");
+    }
+
+    #[test]
+    fn test_zkp_commitments() {
+        let zkp = fixture_zkp();
+        // `a ‖ b ‖ c ‖ commitment ‖ commitment ‖ pok`
+        let mut two = zkp[..EXPECTED_PROOF_SIZE - G1_SIZE].to_vec();
+        two.extend_from_slice(&zkp[EXPECTED_PROOF_SIZE - 2 * G1_SIZE..]);
+        let none = &zkp[..EXPECTED_PROOF_SIZE - 2 * G1_SIZE];
+
+        assert_eq!(ZKP::<BigEndian>::try_from(&zkp[..]).unwrap().proof_commitments.len(), 1);
+        for bytes in [&two[..], none] {
+            assert_eq!(ZKP::<BigEndian>::try_from(bytes).err(), Some(Error::InvalidRawProof));
+        }
+        assert_eq!(ZKP::<BigEndian>::from_gnark(&two).unwrap().proof_commitments.len(), 2);
+        let none = ZKP::<BigEndian>::from_gnark(none).unwrap();
+        assert!(none.proof_commitments.is_empty() && none.proof_commitment_pok.is_none());
     }
 
-    let g16_result = substrate_bn::pairing_batch(&[
-        (proof_a, zkp.proof.b.into()),
-        (public_inputs_msm, -substrate_bn::G2::from(GAMMA_G2)),
-        (proof_c, -substrate_bn::G2::from(DELTA_G2)),
-        (G1::from(ALPHA_G1), -substrate_bn::G2::from(BETA_G2)),
-    ]);
-    if g16_result != substrate_bn::Gt::one() {
-        Err(Error::InvalidProof)
-    } else {
-        Ok(())
+    #[test]
+    fn test_request_digest() {
+        let fixture: Fixture =
+            serde_json::from_str(include_str!("../../../fixtures/valid_block_969001.json"))
+                .unwrap();
+        let request = VerifyZkpRequest::from(fixture.request);
+        let digest = request.digest();
+
+        let mut other = VerifyZkpRequest { height: request.height + 1, ..request };
+        assert_ne!(other.digest(), digest);
+        other.height -= 1;
+        assert_eq!(other.digest(), digest);
+        other.zkp[0] ^= 1;
+        assert_ne!(other.digest(), digest);
     }
 }
@@ -0,0 +1,679 @@
+//! gnark compatible PLONK verification over BN254, with BSB22 custom gate commitments, following
+//! `backend/plonk/bn254/verify.go`.
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::Digest;
+use substrate_bn::{AffineG1, AffineG2, Fr, Group, G1, G2};
+
+use crate::{
+    codec::Reader,
+    groth16::{fr_bytes, hash_to_fr},
+    Error, FQ_SIZE, G1_SIZE, PRIME_R_MINUS_ONE,
+};
+
+/// gnark's domain separation tag of the commitment hash.
+pub const COMMITMENT_DST: &[u8] = b"BSB22-Plonk";
+
+/// The verifying key of the KZG commitment scheme.
+#[derive(Clone, Copy)]
+pub struct KzgVerifyingKey {
+    /// The generator of `G1`.
+    pub g1: AffineG1,
+    /// The generator of `G2` and `[tau]G2`.
+    pub g2: [AffineG2; 2],
+}
+
+pub struct PlonkVerifyingKey {
+    /// The size of the evaluation domain.
+    pub size: u64,
+    pub size_inv: Fr,
+    /// The generator of the evaluation domain.
+    pub generator: Fr,
+    pub nb_public_variables: u64,
+    /// The shift of the cosets of the permutation.
+    pub coset_shift: Fr,
+    /// The commitments to the permutation polynomials.
+    pub s: [AffineG1; 3],
+    pub ql: AffineG1,
+    pub qr: AffineG1,
+    pub qm: AffineG1,
+    pub qo: AffineG1,
+    pub qk: AffineG1,
+    /// The commitments to the selectors of the custom gates, one per BSB22 commitment.
+    pub qcp: Vec<AffineG1>,
+    pub kzg: KzgVerifyingKey,
+    /// The constraint of every BSB22 commitment, after the public inputs.
+    pub commitment_constraint_indexes: Vec<u64>,
+}
+
+/// A KZG proof that a polynomial evaluates to `claimed_value`.
+#[derive(Clone, Copy)]
+pub struct OpeningProof {
+    /// The commitment to the quotient.
+    pub h: AffineG1,
+    pub claimed_value: Fr,
+}
+
+/// A KZG proof that several polynomials evaluate to `claimed_values` at the same point.
+#[derive(Clone)]
+pub struct BatchOpeningProof {
+    pub h: AffineG1,
+    pub claimed_values: Vec<Fr>,
+}
+
+pub struct PlonkProof {
+    /// The commitments to the left, right and output wires.
+    pub lro: [AffineG1; 3],
+    /// The commitment to the permutation accumulator.
+    pub z: AffineG1,
+    /// The commitments to the three parts of the quotient.
+    pub h: [AffineG1; 3],
+    pub bsb22_commitments: Vec<AffineG1>,
+    /// The openings at `zeta` of the linearised polynomial, `l`, `r`, `o`, `s1`, `s2` and the
+    /// custom gate selectors.
+    pub batched_proof: BatchOpeningProof,
+    /// The opening of `z` at `zeta * generator`.
+    pub z_shifted_opening: OpeningProof,
+}
+
+/// The input of the PLONK guest, in gnark's raw encoding.
+#[derive(Deserialize, Serialize)]
+pub struct VerifyPlonkRequest {
+    pub verifying_key: Vec<u8>,
+    pub proof: Vec<u8>,
+    /// Big endian scalars.
+    pub public_inputs: Vec<[u8; 32]>,
+}
+
+pub fn handle_verify_plonk_request(request: VerifyPlonkRequest) -> Result<(), Error> {
+    let public_inputs = request
+        .public_inputs
+        .iter()
+        .map(|input| Fr::from_slice(input).map_err(|_| Error::InvalidPublicInput))
+        .collect::<Result<Vec<_>, _>>()?;
+    verify_plonk(
+        &PlonkVerifyingKey::try_from(&request.verifying_key[..])?,
+        &PlonkProof::try_from(&request.proof[..])?,
+        &public_inputs,
+    )
+}
+
+/// gnark's `fiatshamir.Transcript` with SHA-256: every challenge hashes its name, the previous
+/// challenge and its bindings.
+struct Transcript {
+    previous: Option<[u8; 32]>,
+    bindings: Vec<u8>,
+}
+
+impl Transcript {
+    fn new() -> Self {
+        Self {
+            previous: None,
+            bindings: Vec::new(),
+        }
+    }
+
+    fn bind(&mut self, bytes: &[u8]) {
+        self.bindings.extend_from_slice(bytes);
+    }
+
+    fn compute_challenge(&mut self, name: &[u8]) -> Fr {
+        let mut hasher = Sha256::new().chain_update(name);
+        if let Some(previous) = self.previous {
+            hasher.update(previous);
+        }
+        let challenge: [u8; 32] = hasher.chain_update(&self.bindings).finalize().into();
+        self.bindings.clear();
+        self.previous = Some(challenge);
+        fr_reduce(&challenge)
+    }
+}
+
+/// Verifies `proof` for `public_inputs`.
+pub fn verify_plonk(
+    vk: &PlonkVerifyingKey,
+    proof: &PlonkProof,
+    public_inputs: &[Fr],
+) -> Result<(), Error> {
+    let evaluations = Evaluations::new(vk, proof, public_inputs)?;
+    if linearised_opening(proof, &evaluations) != proof.batched_proof.claimed_values[0] {
+        return Err(Error::InvalidProof);
+    }
+
+    let digests = batch_digests(vk, proof, &evaluations);
+    let zeta = evaluations.zeta;
+    let zu = proof.z_shifted_opening.claimed_value;
+    let (folded_digest, folded_value) =
+        fold_batch_opening(&digests, &proof.batched_proof, zeta, &fr_bytes(&zu));
+
+    kzg_batch_verify(
+        &vk.kzg,
+        &[
+            (
+                folded_digest,
+                OpeningProof {
+                    h: proof.batched_proof.h,
+                    claimed_value: folded_value,
+                },
+                zeta,
+            ),
+            (proof.z.into(), proof.z_shifted_opening, zeta * vk.generator),
+        ],
+    )
+}
+
+/// The challenges and the evaluations at `zeta` that do not depend on the openings.
+struct Evaluations {
+    gamma: Fr,
+    beta: Fr,
+    alpha: Fr,
+    zeta: Fr,
+    /// `Z_H(zeta) = zeta^n - 1`.
+    zh_zeta: Fr,
+    /// `L_1(zeta) = (zeta^n - 1) / (n * (zeta - 1))`.
+    lagrange_one: Fr,
+    /// `PI(zeta)`, the sum of `L_i(zeta) * w_i` over the public inputs and the hashes of the
+    /// commitments at their constraints.
+    pi: Fr,
+}
+
+impl Evaluations {
+    fn new(
+        vk: &PlonkVerifyingKey,
+        proof: &PlonkProof,
+        public_inputs: &[Fr],
+    ) -> Result<Self, Error> {
+        let nb_commitments = vk.qcp.len();
+        if proof.bsb22_commitments.len() != nb_commitments
+            || vk.commitment_constraint_indexes.len() != nb_commitments
+        {
+            return Err(Error::InvalidCommitment);
+        }
+        if proof.batched_proof.claimed_values.len() != 6 + nb_commitments {
+            return Err(Error::InvalidProof);
+        }
+        if public_inputs.len() as u64 != vk.nb_public_variables {
+            return Err(Error::InvalidPublicInput);
+        }
+
+        let mut transcript = Transcript::new();
+        // gamma binds the public data: the permutation, the selectors and the public inputs.
+        for point in vk.s.iter().chain([&vk.ql, &vk.qr, &vk.qm, &vk.qo, &vk.qk]).chain(&vk.qcp) {
+            transcript.bind(&marshal_g1((*point).into()));
+        }
+        for input in public_inputs {
+            transcript.bind(&fr_bytes(input));
+        }
+        for point in &proof.lro {
+            transcript.bind(&marshal_g1((*point).into()));
+        }
+        let gamma = transcript.compute_challenge(b"gamma");
+        let beta = transcript.compute_challenge(b"beta");
+        for point in proof.bsb22_commitments.iter().chain([&proof.z]) {
+            transcript.bind(&marshal_g1((*point).into()));
+        }
+        let alpha = transcript.compute_challenge(b"alpha");
+        for point in &proof.h {
+            transcript.bind(&marshal_g1((*point).into()));
+        }
+        let zeta = transcript.compute_challenge(b"zeta");
+
+        let one = Fr::one();
+        let zh_zeta = pow(zeta, vk.size) - one;
+        let lagrange_one = inverse(zeta - one)? * zh_zeta * vk.size_inv;
+
+        let mut pi = Fr::zero();
+        let mut w_i = one;
+        for input in public_inputs {
+            pi = pi + zh_zeta * vk.size_inv * w_i * inverse(zeta - w_i)? * *input;
+            w_i = w_i * vk.generator;
+        }
+        for (commitment, index) in proof
+            .bsb22_commitments
+            .iter()
+            .zip(&vk.commitment_constraint_indexes)
+        {
+            let hashed = hash_to_fr(&marshal_g1((*commitment).into()), COMMITMENT_DST);
+            let w_i = pow(vk.generator, vk.nb_public_variables + index);
+            pi = pi + w_i * (zeta - one) * inverse(zeta - w_i)? * lagrange_one * hashed;
+        }
+
+        Ok(Self {
+            gamma,
+            beta,
+            alpha,
+            zeta,
+            zh_zeta,
+            lagrange_one,
+            pi,
+        })
+    }
+}
+
+/// The opening of the linearised polynomial at `zeta` the algebraic relation requires, the
+/// negated constant coefficient
+/// `PI(zeta) - alpha^2 * L_1(zeta) + alpha * (l + beta * s1 + gamma) * (r + beta * s2 + gamma)
+/// * (o + gamma) * z(omega * zeta)`.
+fn linearised_opening(proof: &PlonkProof, ev: &Evaluations) -> Fr {
+    let claimed = &proof.batched_proof.claimed_values;
+    let (l, r, o, s1, s2) = (claimed[1], claimed[2], claimed[3], claimed[4], claimed[5]);
+    let zu = proof.z_shifted_opening.claimed_value;
+
+    let permutation = (l + ev.beta * s1 + ev.gamma) * (r + ev.beta * s2 + ev.gamma);
+    let alpha_square_lagrange_one = ev.lagrange_one * ev.alpha * ev.alpha;
+    -(permutation * (o + ev.gamma) * ev.alpha * zu - alpha_square_lagrange_one + ev.pi)
+}
+
+/// The commitments opened at `zeta`: the linearised polynomial, `l`, `r`, `o`, `s1`, `s2` and
+/// the custom gate selectors.
+fn batch_digests(vk: &PlonkVerifyingKey, proof: &PlonkProof, ev: &Evaluations) -> Vec<G1> {
+    let claimed = &proof.batched_proof.claimed_values;
+    let (l, r, o, s1, s2) = (claimed[1], claimed[2], claimed[3], claimed[4], claimed[5]);
+    let zu = proof.z_shifted_opening.claimed_value;
+    let (alpha, beta, gamma, zeta) = (ev.alpha, ev.beta, ev.gamma, ev.zeta);
+
+    // alpha^2 * L_1(zeta) * [Z] + s1_coeff * [S3] + s2_coeff * [Z] + l * [Ql] + l * r * [Qm]
+    //   + r * [Qr] + o * [Qo] + [Qk] + sum(qcp_i * [Pi_i])
+    //   - Z_H(zeta) * ([H0] + zeta^(n + 2) * [H1] + zeta^(2 * (n + 2)) * [H2])
+    let s1_coeff = (l + beta * s1 + gamma) * (r + beta * s2 + gamma) * beta * alpha * zu;
+    let u = vk.coset_shift;
+    let s2_coeff = -((l + beta * zeta + gamma)
+        * (r + beta * u * zeta + gamma)
+        * (o + beta * u * u * zeta + gamma)
+        * alpha);
+    let coeff_z = ev.lagrange_one * alpha * alpha + s2_coeff;
+    let zeta_n_plus_two = pow(zeta, vk.size + 2);
+    let linearised_polynomial_digest = msm(
+        proof
+            .bsb22_commitments
+            .iter()
+            .zip(&claimed[6..])
+            .map(|(point, scalar)| (*point, *scalar))
+            .chain([
+                (vk.ql, l),
+                (vk.qr, r),
+                (vk.qm, l * r),
+                (vk.qo, o),
+                (vk.qk, Fr::one()),
+                (vk.s[2], s1_coeff),
+                (proof.z, coeff_z),
+                (proof.h[0], -ev.zh_zeta),
+                (proof.h[1], -(zeta_n_plus_two * ev.zh_zeta)),
+                (proof.h[2], -(zeta_n_plus_two * zeta_n_plus_two * ev.zh_zeta)),
+            ]),
+    );
+
+    [
+        linearised_polynomial_digest,
+        proof.lro[0].into(),
+        proof.lro[1].into(),
+        proof.lro[2].into(),
+        vk.s[0].into(),
+        vk.s[1].into(),
+    ]
+    .into_iter()
+    .chain(vk.qcp.iter().copied().map(G1::from))
+    .collect()
+}
+
+/// gnark's `kzg.FoldProof`: folds `digests` and `proof.claimed_values` with the powers of a
+/// challenge bound to them, `point` and `data`.
+fn fold_batch_opening(
+    digests: &[G1],
+    proof: &BatchOpeningProof,
+    point: Fr,
+    data: &[u8],
+) -> (G1, Fr) {
+    let mut transcript = Transcript::new();
+    transcript.bind(&fr_bytes(&point));
+    for digest in digests {
+        transcript.bind(&marshal_g1(*digest));
+    }
+    for value in &proof.claimed_values {
+        transcript.bind(&fr_bytes(value));
+    }
+    transcript.bind(data);
+    let gamma = transcript.compute_challenge(b"gamma");
+
+    let mut power = Fr::one();
+    let mut folded = (G1::zero(), Fr::zero());
+    for (digest, value) in digests.iter().zip(&proof.claimed_values) {
+        folded = (folded.0 + *digest * power, folded.1 + *value * power);
+        power = power * gamma;
+    }
+    folded
+}
+
+/// gnark's `kzg.BatchVerifyMultiPoints`, checking
+/// `e(sum(l_i * (D_i - [v_i]G1 + z_i * H_i)), G2) * e(-sum(l_i * H_i), [tau]G2) = 1`.
+///
+/// gnark samples the `l_i` at random, they are derived from the openings instead.
+fn kzg_batch_verify(
+    vk: &KzgVerifyingKey,
+    openings: &[(G1, OpeningProof, Fr)],
+) -> Result<(), Error> {
+    let mut hasher = Sha256::new();
+    for (digest, proof, point) in openings {
+        hasher.update(marshal_g1(*digest));
+        hasher.update(marshal_g1(proof.h.into()));
+        hasher.update(fr_bytes(&proof.claimed_value));
+        hasher.update(fr_bytes(point));
+    }
+    let lambda = fr_reduce(&hasher.finalize().into());
+
+    let mut power = Fr::one();
+    let mut folded_digests = G1::zero();
+    let mut folded_quotients = G1::zero();
+    for (digest, proof, point) in openings {
+        let h = G1::from(proof.h);
+        folded_digests = folded_digests
+            + (*digest - G1::from(vk.g1) * proof.claimed_value + h * *point) * power;
+        folded_quotients = folded_quotients + h * power;
+        power = power * lambda;
+    }
+
+    let result = substrate_bn::pairing_batch(&[
+        (folded_digests, vk.g2[0].into()),
+        (-folded_quotients, G2::from(vk.g2[1])),
+    ]);
+    if result != substrate_bn::Gt::one() {
+        Err(Error::InvalidProof)
+    } else {
+        Ok(())
+    }
+}
+
+fn msm(terms: impl IntoIterator<Item = (AffineG1, Fr)>) -> G1 {
+    terms
+        .into_iter()
+        .fold(G1::zero(), |sum, (point, scalar)| sum + G1::from(point) * scalar)
+}
+
+fn inverse(value: Fr) -> Result<Fr, Error> {
+    value.inverse().ok_or(Error::InvalidProof)
+}
+
+fn pow(base: Fr, mut exponent: u64) -> Fr {
+    let (mut result, mut base) = (Fr::one(), base);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base;
+        }
+        base = base * base;
+        exponent >>= 1;
+    }
+    result
+}
+
+/// gnark's `fr.Element.SetBytes`, reducing modulo `r`.
+fn fr_reduce(bytes: &[u8; 32]) -> Fr {
+    let r = PRIME_R_MINUS_ONE.0 + primitive_types::U256::one();
+    let reduced = primitive_types::U256::from_big_endian(bytes) % r;
+    Fr::new(reduced.0.into()).expect("reduced modulo r; qed;")
+}
+
+/// gnark's `G1Affine.Marshal`: the coordinates big endian, or the infinity flag.
+fn marshal_g1(point: G1) -> [u8; G1_SIZE] {
+    let mut bytes = [0; G1_SIZE];
+    match AffineG1::from_jacobian(point) {
+        Some(point) => {
+            point
+                .x()
+                .to_big_endian(&mut bytes[..FQ_SIZE])
+                .expect("an Fq is 32 bytes; qed;");
+            point
+                .y()
+                .to_big_endian(&mut bytes[FQ_SIZE..])
+                .expect("an Fq is 32 bytes; qed;");
+        }
+        None => bytes[0] = 0b01 << 6,
+    }
+    bytes
+}
+
+impl TryFrom<&[u8]> for PlonkProof {
+    type Error = Error;
+
+    /// `lro`, `z`, `h`, the batched opening, the shifted opening and `bsb22_commitments`, the
+    /// order of gnark's `Proof.WriteRawTo`, which writes the commitments last.
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let mut reader = Reader(value);
+        let lro = [reader.g1()?, reader.g1()?, reader.g1()?];
+        let z = reader.g1()?;
+        let h = [reader.g1()?, reader.g1()?, reader.g1()?];
+        let batched_proof = BatchOpeningProof {
+            h: reader.g1()?,
+            claimed_values: reader.vec(Reader::fr)?,
+        };
+        let z_shifted_opening = OpeningProof {
+            h: reader.g1()?,
+            claimed_value: reader.fr()?,
+        };
+        let proof = PlonkProof {
+            lro,
+            z,
+            h,
+            bsb22_commitments: reader.vec(Reader::g1)?,
+            batched_proof,
+            z_shifted_opening,
+        };
+        reader.finish(proof)
+    }
+}
+
+impl TryFrom<&[u8]> for PlonkVerifyingKey {
+    type Error = Error;
+
+    /// The fields in the order they are declared, `kzg` as `g1` and both `g2`.
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let mut reader = Reader(value);
+        let vk = PlonkVerifyingKey {
+            size: reader.u64()?,
+            size_inv: reader.fr()?,
+            generator: reader.fr()?,
+            nb_public_variables: reader.u64()?,
+            coset_shift: reader.fr()?,
+            s: [reader.g1()?, reader.g1()?, reader.g1()?],
+            ql: reader.g1()?,
+            qr: reader.g1()?,
+            qm: reader.g1()?,
+            qo: reader.g1()?,
+            qk: reader.g1()?,
+            qcp: reader.vec(Reader::g1)?,
+            kzg: KzgVerifyingKey {
+                g1: reader.g1()?,
+                g2: [reader.g2()?, reader.g2()?],
+            },
+            commitment_constraint_indexes: reader.vec(Reader::u64)?,
+        };
+        if vk.size_inv * Fr::new([vk.size, 0, 0, 0].into()).ok_or(Error::InvalidVerifyingKey)?
+            != Fr::one()
+        {
+            return Err(Error::InvalidVerifyingKey);
+        }
+        reader.finish(vk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn scalar(value: u64) -> Fr {
+        Fr::new([value, 0, 0, 0].into()).unwrap()
+    }
+
+    fn g1(value: u64) -> AffineG1 {
+        AffineG1::from_jacobian(G1::one() * scalar(value)).unwrap()
+    }
+
+    /// `(D - [v]G1) / (tau - point)`, the KZG opening of any digest to any value, with the
+    /// trapdoor `tau`.
+    fn open(digest: G1, value: Fr, point: Fr, tau: Fr) -> AffineG1 {
+        let quotient = (digest - G1::one() * value) * (tau - point).inverse().unwrap();
+        AffineG1::from_jacobian(quotient).unwrap()
+    }
+
+    /// A key and a proof satisfying the checks of the verifier, opened with the trapdoor instead
+    /// of polynomials, so it only checks the verifier is consistent with itself; compatibility
+    /// with gnark is checked by [`test_gnark_proof`].
+    fn instance(public_inputs: &[Fr], nb_commitments: usize) -> (PlonkVerifyingKey, PlonkProof) {
+        let tau = scalar(1_000_003);
+        let vk = PlonkVerifyingKey {
+            size: 16,
+            size_inv: scalar(16).inverse().unwrap(),
+            generator: scalar(7),
+            nb_public_variables: public_inputs.len() as u64,
+            coset_shift: scalar(5),
+            s: [g1(11), g1(12), g1(13)],
+            ql: g1(14),
+            qr: g1(15),
+            qm: g1(16),
+            qo: g1(17),
+            qk: g1(18),
+            qcp: (0..nb_commitments as u64).map(|i| g1(19 + i)).collect(),
+            kzg: KzgVerifyingKey {
+                g1: AffineG1::from_jacobian(G1::one()).unwrap(),
+                g2: [
+                    AffineG2::from_jacobian(G2::one()).unwrap(),
+                    AffineG2::from_jacobian(G2::one() * tau).unwrap(),
+                ],
+            },
+            commitment_constraint_indexes: (0..nb_commitments as u64).map(|i| 3 + i).collect(),
+        };
+        let mut proof = PlonkProof {
+            lro: [g1(31), g1(32), g1(33)],
+            z: g1(34),
+            h: [g1(35), g1(36), g1(37)],
+            bsb22_commitments: (0..nb_commitments as u64).map(|i| g1(38 + i)).collect(),
+            batched_proof: BatchOpeningProof {
+                h: g1(1),
+                claimed_values: (0..6 + nb_commitments as u64).map(|i| scalar(51 + i)).collect(),
+            },
+            z_shifted_opening: OpeningProof {
+                h: g1(1),
+                claimed_value: scalar(61),
+            },
+        };
+
+        let ev = Evaluations::new(&vk, &proof, public_inputs).unwrap();
+        proof.batched_proof.claimed_values[0] = linearised_opening(&proof, &ev);
+        let zu = proof.z_shifted_opening.claimed_value;
+        let (digest, value) = fold_batch_opening(
+            &batch_digests(&vk, &proof, &ev),
+            &proof.batched_proof,
+            ev.zeta,
+            &fr_bytes(&zu),
+        );
+        proof.batched_proof.h = open(digest, value, ev.zeta, tau);
+        proof.z_shifted_opening.h = open(proof.z.into(), zu, ev.zeta * vk.generator, tau);
+        (vk, proof)
+    }
+
+    #[test]
+    fn test_verify_plonk() {
+        let inputs = [scalar(1), scalar(2)];
+        for nb_commitments in [0, 2] {
+            let (vk, proof) = instance(&inputs, nb_commitments);
+            assert_eq!(verify_plonk(&vk, &proof, &inputs), Ok(()));
+            assert_eq!(
+                verify_plonk(&vk, &proof, &[scalar(1), scalar(3)]),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                verify_plonk(&vk, &proof, &inputs[..1]),
+                Err(Error::InvalidPublicInput)
+            );
+
+            let mut tampered = instance(&inputs, nb_commitments).1;
+            tampered.batched_proof.claimed_values[1] = scalar(1);
+            assert_eq!(verify_plonk(&vk, &tampered, &inputs), Err(Error::InvalidProof));
+
+            // Consistent with the algebraic relation, but not with the commitments.
+            let mut tampered = instance(&inputs, nb_commitments).1;
+            tampered.z_shifted_opening.h = g1(2);
+            assert_eq!(verify_plonk(&vk, &tampered, &inputs), Err(Error::InvalidProof));
+        }
+    }
+
+    /// Generated by gnark with `fixtures/gnark`: a proof with one BSB22 commitment.
+    fn gnark_fixture() -> VerifyPlonkRequest {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../fixtures/plonk/commitment.json");
+        let json = std::fs::read(path)
+            .unwrap_or_else(|err| panic!("{path}: {err}, generate it with fixtures/gnark"));
+        let fixture: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let hex_field = |name: &str| hex::decode(fixture[name].as_str().unwrap()).unwrap();
+        VerifyPlonkRequest {
+            verifying_key: hex_field("verifying_key"),
+            proof: hex_field("proof"),
+            public_inputs: fixture["public_inputs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|input| hex::decode(input.as_str().unwrap()).unwrap().try_into().unwrap())
+                .collect(),
+        }
+    }
+
+    #[test]
+    #[ignore = "needs fixtures/plonk/commitment.json, which is generated with gnark"]
+    fn test_gnark_proof() {
+        let request = gnark_fixture();
+        let proof = PlonkProof::try_from(&request.proof[..]).unwrap();
+        assert_eq!(proof.bsb22_commitments.len(), 1);
+        assert_eq!(handle_verify_plonk_request(request), Ok(()));
+
+        let mut tampered = gnark_fixture();
+        tampered.public_inputs[0][31] ^= 1;
+        assert_eq!(handle_verify_plonk_request(tampered), Err(Error::InvalidProof));
+    }
+
+    #[test]
+    fn test_transcript() {
+        // `sha256("gamma" || binding)`, then `sha256("beta" || gamma)`.
+        let mut transcript = Transcript::new();
+        transcript.bind(b"binding");
+        let gamma: [u8; 32] = Sha256::digest(b"gammabinding").into();
+        assert!(transcript.compute_challenge(b"gamma") == fr_reduce(&gamma));
+        let beta: [u8; 32] = Sha256::new()
+            .chain_update(b"beta")
+            .chain_update(gamma)
+            .finalize()
+            .into();
+        assert!(transcript.compute_challenge(b"beta") == fr_reduce(&beta));
+    }
+
+    #[test]
+    fn test_decode_proof() {
+        let point = |value| marshal_g1(g1(value).into()).to_vec();
+        let mut bytes: Vec<u8> = (1..=7).flat_map(point).collect();
+        bytes.extend(point(8));
+        bytes.extend(2u32.to_be_bytes());
+        bytes.extend(fr_bytes(&scalar(9)));
+        bytes.extend(fr_bytes(&scalar(10)));
+        bytes.extend(point(11));
+        bytes.extend(fr_bytes(&scalar(12)));
+        bytes.extend(1u32.to_be_bytes());
+        bytes.extend(point(13));
+
+        let proof = PlonkProof::try_from(&bytes[..]).unwrap();
+        assert!(proof.h[2] == g1(7));
+        assert!(proof.batched_proof.h == g1(8));
+        assert!(proof.batched_proof.claimed_values == vec![scalar(9), scalar(10)]);
+        assert!(proof.z_shifted_opening.h == g1(11));
+        assert!(proof.z_shifted_opening.claimed_value == scalar(12));
+        assert!(proof.bsb22_commitments == vec![g1(13)]);
+
+        assert!(matches!(
+            PlonkProof::try_from(&bytes[..bytes.len() - 1]),
+            Err(Error::InvalidRawProof)
+        ));
+        bytes.push(0);
+        assert!(matches!(PlonkProof::try_from(&bytes[..]), Err(Error::InvalidRawProof)));
+    }
+}
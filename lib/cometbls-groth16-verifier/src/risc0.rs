@@ -0,0 +1,306 @@
+//! Verification of RISC Zero Groth16 receipts, the last step of `SNARK -> STARK -> SNARK`, the way
+//! `RiscZeroGroth16Verifier.sol` verifies them.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use byteorder::BigEndian;
+use hex_literal::hex;
+use sha2::Sha256;
+use sha3::Digest;
+use substrate_bn::Fr;
+
+use crate::{
+    verify_groth16, ByteArrayExt, CommitmentHash, Error, G1AffineBE, G2AffineBE, Proof,
+    VerifyingKey, G1_SIZE, G2_SIZE, H256, ZKP,
+};
+
+/// `a`, `b` and `c` of the proof, in the layout of [`crate::RawZKP`]. Seals for the verifier
+/// router are prefixed with a four byte selector, which is not part of it.
+pub const SEAL_SIZE: usize = G1_SIZE + G2_SIZE + G1_SIZE;
+
+/// The control root, the BN254 control ID and the verifying key, all of which
+/// `Groth16ReceiptVerifierParameters` of the RISC Zero release the receipts were proven with
+/// defines.
+pub struct VerifierParameters {
+    /// The root of the recursion programs allowed to produce the lifted receipt.
+    pub control_root: H256,
+    /// The ID of the identity program, committed in the Groth16 circuit.
+    ///
+    /// In the byte order of risc0's `Digest`, `BN254_IDENTITY_CONTROL_ID`, which is the reverse of
+    /// the `BN254_CONTROL_ID` constant of `ControlID.sol`.
+    pub bn254_control_id: H256,
+    /// The key of the stark-to-snark circuit, with five public inputs and no commitments.
+    pub verifying_key: VerifyingKey,
+}
+
+/// `ALLOWED_CONTROL_ROOT` of RISC Zero 1.1, `CONTROL_ROOT` of `ControlID.sol`.
+pub const CONTROL_ROOT_1_1: H256 =
+    hex!("8b6dcf11d463ac455361b41fb3ed053febb817491bdea00fdb340e45013b852e");
+/// `BN254_IDENTITY_CONTROL_ID` of RISC Zero 1.1, see [`VerifierParameters::bn254_control_id`].
+pub const BN254_CONTROL_ID_1_1: H256 =
+    hex!("4e160df1e119ac0e3d658755a9edf38c8feb307b34bc10b57f4538dbe122a005");
+
+// The points of `Groth16Verifier.sol`, big endian, `G2` imaginary parts first.
+const ALPHA_G1: [u8; G1_SIZE] = hex!("2d4d9aa7e302d9df41749d5507949d05dbea33fbb16c643b22f599a2be6df2e214bedd503c37ceb061d8ec60209fe345ce89830a19230301f076caff004d1926");
+const BETA_G2: [u8; G2_SIZE] = hex!("0967032fcbf776d1afc985f88877f182d38480a653f2decaa9794cbc3bf3060c0e187847ad4c798374d0d6732bf501847dd68bc0e071241e0213bc7fc13db7ab304cfbd1e08a704a99f5e847d93f8c3caafddec46b7a0d379da69a4d112346a71739c1b1a457a8c7313123d24d2f9192f896b7c63eea05a9d57f06547ad0cec8");
+const GAMMA_G2: [u8; G2_SIZE] = hex!("198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa");
+const DELTA_G2: [u8; G2_SIZE] = hex!("03b03cd5effa95ac9bee94f1f5ef907157bda4812ccf0b4c91f42bb629f83a1c1aa085ff28179a12d922dba0547057ccaae94b9d69cfaa4e60401fea7f3e0333110c10134f200b19f6490846d518c9aea868366efb7228ca5c91d2940d0307621e60f31fcbf757e837e867178318832d0b2d74d59e2fea1c7142df187d3fc6d3");
+const GAMMA_ABC_G1: [[u8; G1_SIZE]; 6] = [
+    hex!("12ac9a25dcd5e1a832a9061a082c15dd1d61aa9c4d553505739d0f5d65dc3be4025aa744581ebe7ad91731911c898569106ff5a2d30f3eee2b23c60ee980acd4"),
+    hex!("0707b920bc978c02f292fae2036e057be54294114ccc3c8769d883f688a1423f2e32a094b7589554f7bc357bf63481acd2d55555c203383782a4650787ff6642"),
+    hex!("0bca36e2cbe6394b3e249751853f961511011c7148e336f4fd974644850fc3472ede7c9acf48cf3a3729fa3d68714e2a8435d4fa6db8f7f409c153b1fcdf9b8b"),
+    hex!("1b8af999dbfbb3927c091cc2aaf201e488cbacc3e2c6b6fb5a25f9112e04f2a72b91a26aa92e1b6f5722949f192a81c850d586d81a60157f3e9cf04f679cccd6"),
+    hex!("2b5f494ed674235b8ac1750bdfd5a7615f002d4a1dcefeddd06eda5a076ccd0d2fe520ad2020aab9cbba817fcbb9a863b8a76ff88f14f912c5e71665b2ad5e82"),
+    hex!("0f1c3c0d5d9da0fa03666843cde4e82e869ba5252fce3c25d5940320b1c4d493214bfcff74f425f6fe8c0d07b307482d8bc8bb2f3608f68287aa01bd0b69e809"),
+];
+
+impl VerifierParameters {
+    /// The parameters of RISC Zero 1.1, `Groth16ReceiptVerifierParameters::default()` of
+    /// `risc0-zkvm` 1.1.
+    pub fn v1_1() -> Self {
+        let g1 = |bytes: [u8; G1_SIZE]| {
+            G1AffineBE::try_from(bytes).expect("a point of the release; qed;").1
+        };
+        let g2 = |bytes: [u8; G2_SIZE]| {
+            G2AffineBE::try_from(bytes).expect("a point of the release; qed;").1
+        };
+        Self {
+            control_root: CONTROL_ROOT_1_1,
+            bn254_control_id: BN254_CONTROL_ID_1_1,
+            verifying_key: VerifyingKey {
+                alpha_g1: g1(ALPHA_G1),
+                beta_g2: g2(BETA_G2),
+                gamma_g2: g2(GAMMA_G2),
+                delta_g2: g2(DELTA_G2),
+                gamma_abc_g1: GAMMA_ABC_G1.into_iter().map(g1).collect(),
+                commitment_key: None,
+                public_and_commitment_committed: Vec::new(),
+                commitment_hash: CommitmentHash::Gnark,
+            },
+        }
+    }
+}
+
+/// Verifies `seal` proves the receipt claim with `claim_digest`, see [`receipt_claim_digest`].
+pub fn verify_seal(
+    params: &VerifierParameters,
+    seal: &[u8],
+    claim_digest: H256,
+) -> Result<(), Error> {
+    let seal = <[u8; SEAL_SIZE]>::try_from(seal).map_err(|_| Error::InvalidRawProof)?;
+    let zkp = ZKP::<BigEndian> {
+        proof: Proof {
+            a: G1AffineBE::try_from(seal.array_slice::<0, G1_SIZE>())?.1,
+            b: G2AffineBE::try_from(seal.array_slice::<G1_SIZE, G2_SIZE>())?.1,
+            c: G1AffineBE::try_from(seal.array_slice::<{ G1_SIZE + G2_SIZE }, G1_SIZE>())?.1,
+        },
+        proof_commitments: Vec::new(),
+        proof_commitment_pok: None,
+        _marker: PhantomData,
+    };
+
+    let (control_root_0, control_root_1) = split_digest(params.control_root)?;
+    let (claim_0, claim_1) = split_digest(claim_digest)?;
+    let mut bn254_control_id = params.bn254_control_id;
+    bn254_control_id.reverse();
+    let public_inputs = [
+        control_root_0,
+        control_root_1,
+        claim_0,
+        claim_1,
+        Fr::from_slice(&bn254_control_id).map_err(|_| Error::InvalidPublicInput)?,
+    ];
+
+    if params.verifying_key.gamma_abc_g1.len() != public_inputs.len() + 1 {
+        return Err(Error::InvalidVerifyingKey);
+    }
+    verify_groth16(&params.verifying_key, &zkp, &public_inputs)
+}
+
+/// The digest of the claim that the guest `image_id` halted successfully, committing a journal
+/// with `journal_digest` and without assumptions, as `ReceiptClaimLib.ok` computes it.
+pub fn receipt_claim_digest(image_id: H256, journal_digest: H256) -> H256 {
+    // The post state of a halted guest, with a zero program counter and memory root.
+    let post_state = tagged_struct(b"risc0.SystemState", &[[0; 32]], &[0]);
+    // The journal and the (empty) assumptions.
+    let output = tagged_struct(b"risc0.Output", &[journal_digest, [0; 32]], &[]);
+    // The input, which is unused, and the system and user exit codes, both zero.
+    tagged_struct(
+        b"risc0.ReceiptClaim",
+        &[[0; 32], image_id, post_state, output],
+        &[0, 0],
+    )
+}
+
+/// RISC Zero's structured hash: the tag, the digests of the fields, the words of the data and
+/// the number of digests.
+fn tagged_struct(tag: &[u8], down: &[H256], data: &[u32]) -> H256 {
+    let mut hasher = Sha256::new().chain_update(Sha256::digest(tag));
+    for digest in down {
+        hasher.update(digest);
+    }
+    for word in data {
+        hasher.update(word.to_le_bytes());
+    }
+    hasher.update((down.len() as u16).to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Splits `digest`, read as a little endian integer, into its lower and upper 128 bits.
+fn split_digest(mut digest: H256) -> Result<(Fr, Fr), Error> {
+    digest.reverse();
+    let half = |bytes: [u8; 16]| {
+        let mut scalar = [0; 32];
+        scalar[16..].copy_from_slice(&bytes);
+        Fr::from_slice(&scalar).map_err(|_| Error::InvalidPublicInput)
+    };
+    Ok((
+        half(digest.array_slice::<16, 16>())?,
+        half(digest.array_slice::<0, 16>())?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use substrate_bn::Fq;
+
+    use super::*;
+    use crate::{
+        curve::Bn254,
+        tests::{groth16_instance, Instance},
+    };
+
+    fn scalar(value: &str) -> Fr {
+        Fr::from_str(value).unwrap()
+    }
+
+    fn fq_bytes(fq: Fq) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        fq.to_big_endian(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Parameters and a seal for them, see [`groth16_instance`].
+    fn instance(
+        control_root: H256,
+        bn254_control_id: H256,
+        claim_digest: H256,
+    ) -> (VerifierParameters, Vec<u8>) {
+        let (control_root_0, control_root_1) = split_digest(control_root).unwrap();
+        let (claim_0, claim_1) = split_digest(claim_digest).unwrap();
+        let mut id = bn254_control_id;
+        id.reverse();
+        let inputs = [
+            control_root_0,
+            control_root_1,
+            claim_0,
+            claim_1,
+            Fr::from_slice(&id).unwrap(),
+        ];
+        let Instance { vk, proof, .. } = groth16_instance::<Bn254>(&inputs, Vec::new());
+
+        let mut seal = Vec::new();
+        seal.extend(fq_bytes(proof.a.x()));
+        seal.extend(fq_bytes(proof.a.y()));
+        seal.extend(fq_bytes(proof.b.x().imaginary()));
+        seal.extend(fq_bytes(proof.b.x().real()));
+        seal.extend(fq_bytes(proof.b.y().imaginary()));
+        seal.extend(fq_bytes(proof.b.y().real()));
+        seal.extend(fq_bytes(proof.c.x()));
+        seal.extend(fq_bytes(proof.c.y()));
+        let params = VerifierParameters {
+            control_root,
+            bn254_control_id,
+            verifying_key: vk,
+        };
+        (params, seal)
+    }
+
+    #[test]
+    fn test_system_state_digest() {
+        // `SYSTEM_STATE_ZERO_DIGEST` of `StructHash.sol`.
+        assert_eq!(
+            tagged_struct(b"risc0.SystemState", &[[0; 32]], &[0]),
+            hex!("a3acc27117418996340b84e5a90f3ef4c49d22c79e44aad822ec9c313e1eb8e2")
+        );
+    }
+
+    #[test]
+    fn test_split_digest() {
+        let mut digest = [0; 32];
+        digest[0] = 1;
+        digest[31] = 2;
+        let (low, high) = split_digest(digest).unwrap();
+        assert!(low == Fr::one());
+        assert!(high == scalar("2") * scalar("1329227995784915872903807060280344576"));
+    }
+
+    #[test]
+    fn test_verify_seal() {
+        let claim_digest = receipt_claim_digest([1; 32], [2; 32]);
+        let (params, seal) = instance([3; 32], [4; 32], claim_digest);
+        assert_eq!(verify_seal(&params, &seal, claim_digest), Ok(()));
+
+        assert_eq!(
+            verify_seal(&params, &seal, receipt_claim_digest([1; 32], [5; 32])),
+            Err(Error::InvalidProof)
+        );
+        assert_eq!(
+            verify_seal(&params, &seal[..SEAL_SIZE - 1], claim_digest),
+            Err(Error::InvalidRawProof)
+        );
+
+        let mut tampered = seal.clone();
+        tampered[G1_SIZE + G2_SIZE..].copy_from_slice(&seal[..G1_SIZE]);
+        assert_eq!(
+            verify_seal(&params, &tampered, claim_digest),
+            Err(Error::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn test_v1_1() {
+        let params = VerifierParameters::v1_1();
+        assert_eq!(params.verifying_key.gamma_abc_g1.len(), 6);
+        // `BN254_CONTROL_ID` of `ControlID.sol`.
+        let mut bn254_control_id = params.bn254_control_id;
+        bn254_control_id.reverse();
+        assert_eq!(
+            bn254_control_id,
+            hex!("05a022e1db38457fb510bc347b30eb8f8cf3eda95587653d0eac19e1f10d164e")
+        );
+    }
+
+    /// Written by the host's `calldata --json` for a receipt of the verifier guest, proven with
+    /// RISC Zero 1.1 and `SNARK_WRAPPING=1`: the seal and the claim digest.
+    fn receipt_fixture() -> (Vec<u8>, H256) {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../fixtures/risc0/groth16.json");
+        let json = std::fs::read(path)
+            .unwrap_or_else(|err| panic!("{path}: {err}, generate it with `calldata --json`"));
+        let fixture: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let hex_field = |name: &str| {
+            let value = fixture[name].as_str().unwrap();
+            hex::decode(value.strip_prefix("0x").unwrap_or(value)).unwrap()
+        };
+        // Without the selector of the verifier router.
+        let seal = hex_field("seal")[4..].to_vec();
+        let claim_digest = receipt_claim_digest(
+            hex_field("image_id").try_into().unwrap(),
+            hex_field("journal_digest").try_into().unwrap(),
+        );
+        (seal, claim_digest)
+    }
+
+    #[test]
+    #[ignore = "needs fixtures/risc0/groth16.json, which is generated with the Groth16 prover"]
+    fn test_verify_receipt() {
+        let (seal, claim_digest) = receipt_fixture();
+        let params = VerifierParameters::v1_1();
+        assert_eq!(verify_seal(&params, &seal, claim_digest), Ok(()));
+
+        let mut other = claim_digest;
+        other[0] ^= 1;
+        assert_eq!(verify_seal(&params, &seal, other), Err(Error::InvalidProof));
+    }
+}
@@ -0,0 +1,12 @@
+use cometbls_groth16_verifier::{handle_verify_zkp_request, VerifyZkpRequest};
+use risc0_zkvm::guest::env;
+
+/// Verifies every request in a single execution, the baseline for aggregating separate proofs.
+fn main() {
+    let requests: Vec<VerifyZkpRequest> = env::read();
+    let results: Vec<bool> = requests
+        .into_iter()
+        .map(|request| handle_verify_zkp_request(request).is_ok())
+        .collect();
+    env::commit(&results);
+}
@@ -0,0 +1,9 @@
+use cometbls_groth16_verifier::plonk::{handle_verify_plonk_request, VerifyPlonkRequest};
+use risc0_zkvm::guest::env;
+
+/// Verifies a gnark PLONK proof, to compare its cycles with the Groth16 verifier guest.
+fn main() {
+    let request: VerifyPlonkRequest = env::read();
+    let result = handle_verify_plonk_request(request);
+    env::commit(&result.is_ok());
+}
@@ -1,8 +1,25 @@
-use cometbls_groth16_verifier::{handle_verify_zkp_request, VerifyZkpRequest};
+#[cfg(not(feature = "abi-journal"))]
+use cometbls_groth16_verifier::handle_verify_zkp_request;
+use cometbls_groth16_verifier::VerifyZkpRequest;
 use risc0_zkvm::guest::env;
 
+/// Commits the digest of the request and whether it is valid, see [`VerifyZkpRequest::digest`].
+#[cfg(not(feature = "abi-journal"))]
 fn main() {
     let request: VerifyZkpRequest = env::read();
+    let digest = request.digest();
     let result = handle_verify_zkp_request(request);
-    env::commit(&result.is_ok());
+    env::commit(&(digest, result.is_ok()));
+}
+
+/// Commits the verified header Solidity ABI encoded, so contracts can decode the journal. The
+/// journal of an invalid request is empty.
+#[cfg(feature = "abi-journal")]
+fn main() {
+    use cometbls_groth16_verifier::abi::VerifiedHeader;
+
+    let request: VerifyZkpRequest = env::read();
+    if let Ok(header) = VerifiedHeader::verify(request) {
+        env::commit_slice(&header.abi_encode());
+    }
 }